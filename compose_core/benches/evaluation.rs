@@ -0,0 +1,117 @@
+// Copyright Rob Gage 2025
+
+use compose_core::{
+    FunctionReference,
+    Namespace,
+    UnresolvedFunction,
+};
+use pups::*;
+use std::{
+    alloc::{
+        GlobalAlloc,
+        Layout,
+        System,
+    },
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// An allocator that counts the allocations it makes, so that each benchmark can report how many
+/// allocations it needs
+struct CountingAllocator;
+
+/// The number of allocations made by the `CountingAllocator` so far
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) { System.dealloc(pointer, layout) }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(pointer, layout, size)
+    }
+
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of times each benchmark is evaluated
+const ITERATIONS: u32 = 20;
+
+/// Functions defined before the benchmarks are evaluated
+const DEFINITIONS: [&str; 3] = [
+    "countdown : copy 0 = ( ) ( 1 - countdown ) ? ;",
+    "fibonacci : copy 2 < ( ) ( copy 1 - fibonacci swap 2 - fibonacci + ) ? ;",
+    "square : [x] -> ( x x * ) ;",
+];
+
+/// Benchmarks of function and lambda application, each as a name and the free terms it evaluates,
+/// where `iota` shows the time and allocations spent making the lists that the others process
+const BENCHMARKS: [(&str, &str); 10] = [
+    ("iota", "10000 iota"),
+    ("map", "10000 iota ( 1 + ) map"),
+    ("map composed", "10000 iota ( 2 * ) ( 1 + ) compose map"),
+    ("map curried", "10000 iota 3 ( + ) curry map"),
+    ("filter", "10000 iota ( 2 % 0 = ) filter"),
+    ("fold", "0 10000 iota ( + ) fold"),
+    ("dip", "10000 iota ( 1 ( 2 * ) dip drop ) map"),
+    ("countdown", "100000 countdown"),
+    ("fibonacci", "20 fibonacci"),
+    ("locals", "10000 iota ( square ) map"),
+];
+
+/// Evaluates each benchmark and prints the mean time it took and the mean number of allocations
+/// it made
+fn main() {
+    let mut namespace: Namespace = Namespace::new();
+    for source in DEFINITIONS {
+        let input: Text = Text::from_string(source);
+        let ParseResult::Success (function, _) = UnresolvedFunction::parse
+            .then_ignore(end())
+            .parse(&input)
+        else { panic!("Definition `{}` can be parsed", source) };
+        if namespace.define(&function).is_err() {
+            panic!("Definition `{}` can be resolved", source);
+        }
+    }
+    for (name, source) in BENCHMARKS {
+        let input: Text = Text::from_string(source);
+        let ParseResult::Success (function, _) = UnresolvedFunction::parse_free_terms
+            .then_ignore(end())
+            .parse(&input)
+        else { panic!("Benchmark `{}` can be parsed", name) };
+        let reference: FunctionReference = namespace.define(&function)
+            .unwrap_or_else(|_| panic!("Benchmark `{}` can be resolved", name));
+        let mut total: Duration = Duration::ZERO;
+        let mut allocations: usize = 0;
+        for _ in 0..ITERATIONS {
+            let mut virtual_machine = namespace.create_virtual_machine();
+            let allocated: usize = ALLOCATIONS.load(Ordering::Relaxed);
+            let start: Instant = Instant::now();
+            if virtual_machine.evaluate(reference).is_err() {
+                panic!("Benchmark `{}` can be evaluated", name);
+            }
+            total += start.elapsed();
+            allocations += ALLOCATIONS.load(Ordering::Relaxed) - allocated;
+        }
+        println!(
+            "{:<16} {:>10.3?} {:>10} allocations",
+            name,
+            total / ITERATIONS,
+            allocations / ITERATIONS as usize
+        );
+    }
+}
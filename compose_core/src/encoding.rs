@@ -0,0 +1,113 @@
+// Copyright Rob Gage 2025
+
+/// The digits used to encode bytes in hexadecimal
+const HEXADECIMAL_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// The digits used to encode bytes in standard base64
+const BASE64_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as lowercase hexadecimal ASCII text
+pub fn encode_hexadecimal(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded: Vec<u8> = Vec::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        encoded.push(HEXADECIMAL_DIGITS[(byte >> 4) as usize]);
+        encoded.push(HEXADECIMAL_DIGITS[(byte & 0xf) as usize]);
+    }
+    encoded
+}
+
+/// Decodes hexadecimal ASCII text in either case into bytes, returning `None` if it has an odd
+/// length or a character that is not a hexadecimal digit
+pub fn decode_hexadecimal(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) { return None; }
+    text.chunks(2)
+        .map(|pair| Some ((hexadecimal_value(pair[0])? << 4) | hexadecimal_value(pair[1])?))
+        .collect()
+}
+
+/// Encodes bytes as standard base64 ASCII text with padding
+pub fn encode_base64(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded: Vec<u8> = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group: u32 = chunk.iter()
+            .enumerate()
+            .fold(0, |group, (index, byte)| group | ((*byte as u32) << (16 - 8 * index)));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64_DIGITS[((group >> (18 - 6 * index)) & 0x3f) as usize]);
+            } else { encoded.push(b'='); }
+        }
+    }
+    encoded
+}
+
+/// Decodes standard base64 ASCII text with padding into bytes, returning `None` if it is malformed
+pub fn decode_base64(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(4) { return None; }
+    let mut decoded: Vec<u8> = Vec::with_capacity(text.len() / 4 * 3);
+    let chunk_count: usize = text.len() / 4;
+    for (chunk_index, chunk) in text.chunks(4).enumerate() {
+        // padding may only appear at the end of the last chunk
+        let padding: usize = chunk.iter().rev().take_while(|digit| **digit == b'=').count();
+        if padding > 2 || (padding > 0 && chunk_index + 1 != chunk_count) { return None; }
+        let mut group: u32 = 0;
+        for (index, digit) in chunk[..4 - padding].iter().enumerate() {
+            group |= (base64_value(*digit)? as u32) << (18 - 6 * index);
+        }
+        for index in 0..3 - padding {
+            decoded.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some (decoded)
+}
+
+/// Returns the value of a base64 digit
+fn base64_value(digit: u8) -> Option<u8> {
+    BASE64_DIGITS.iter().position(|candidate| *candidate == digit).map(|value| value as u8)
+}
+
+/// Returns the value of a hexadecimal digit in either case
+fn hexadecimal_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn hexadecimal_round_trips() {
+        assert_eq!(encode_hexadecimal(&[0xde, 0xad, 0x01]), b"dead01");
+        assert_eq!(decode_hexadecimal(b"DEad01"), Some (vec![0xde, 0xad, 0x01]));
+        assert_eq!(decode_hexadecimal(b""), Some (vec![]));
+    }
+
+    #[test]
+    fn malformed_hexadecimal_is_refused() {
+        assert_eq!(decode_hexadecimal(b"abc"), None);
+        assert_eq!(decode_hexadecimal(b"zz"), None);
+    }
+
+    #[test]
+    fn base64_is_padded() {
+        assert_eq!(encode_base64(b"foo"), b"Zm9v");
+        assert_eq!(encode_base64(b"fo"), b"Zm8=");
+        assert_eq!(encode_base64(b"f"), b"Zg==");
+        assert_eq!(encode_base64(b""), b"");
+        assert_eq!(decode_base64(b"Zm8="), Some (b"fo".to_vec()));
+        assert_eq!(decode_base64(b"Zg=="), Some (b"f".to_vec()));
+    }
+
+    #[test]
+    fn malformed_base64_is_refused() {
+        assert_eq!(decode_base64(b"Zm8"), None);
+        assert_eq!(decode_base64(b"Z==="), None);
+        assert_eq!(decode_base64(b"Zg==Zm9v"), None);
+        assert_eq!(decode_base64(b"Zm9!"), None);
+    }
+
+}
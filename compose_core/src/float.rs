@@ -0,0 +1,162 @@
+// Copyright Rob Gage 2025
+
+use std::{
+    cmp::Ordering,
+    fmt::{
+        Display,
+        Formatter,
+        self,
+    },
+    hash::{
+        Hash,
+        Hasher,
+    },
+    ops::{
+        Add,
+        Div,
+        Mul,
+        Rem,
+        Sub
+    },
+    str::FromStr
+};
+
+/// An IEEE double-precision floating-point number, where every NaN is equal to itself and greater
+/// than every other `Float`, and negative zero is equal to zero
+#[derive(Clone, Copy, Debug)]
+pub struct Float (f64);
+
+impl Float {
+
+    /// Creates a `Float` from a string slice such as `1.5e3`
+    pub fn from_string(string: &str) -> Option<Self> {
+        f64::from_str(string).ok().map(Self)
+    }
+
+    /// Creates a `Float` from an `f64`
+    pub const fn new(float: f64) -> Self { Self (float) }
+
+    /// Returns the `f64` value of this `Float`
+    pub const fn value(&self) -> f64 { self.0 }
+
+    /// Returns this `Float` with every NaN replaced by the same NaN and negative zero replaced by
+    /// zero, so that equal `Float`s are ordered as equal
+    fn canonical(&self) -> f64 {
+        if self.0.is_nan() { f64::NAN } else if self.0 == 0.0 { 0.0 } else { self.0 }
+    }
+
+}
+
+impl Display for Float {
+
+    /// Writes the `Float` in scientific notation, so that it is never confused with an exact number
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { write!(f, "{:e}", self.0) }
+
+}
+
+impl Eq for Float { }
+
+impl Hash for Float {
+
+    fn hash<H: Hasher>(&self, state: &mut H) { self.canonical().to_bits().hash(state) }
+
+}
+
+impl Ord for Float {
+
+    fn cmp(&self, other: &Self) -> Ordering { self.canonical().total_cmp(&other.canonical()) }
+
+}
+
+impl PartialEq for Float {
+
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+
+}
+
+impl PartialOrd for Float {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+
+}
+
+impl Add for Float {
+    type Output = Self;
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self (self.0 + other.0)
+    }
+}
+
+impl Div for Float {
+    type Output = Self;
+    #[inline]
+    fn div(self, other: Self) -> Self {
+        Self (self.0 / other.0)
+    }
+}
+
+impl Mul for Float {
+    type Output = Self;
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        Self (self.0 * other.0)
+    }
+}
+
+impl Rem for Float {
+    type Output = Self;
+    #[inline]
+    fn rem(self, other: Self) -> Self {
+        Self (self.0 % other.0)
+    }
+}
+
+impl Sub for Float {
+    type Output = Self;
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self (self.0 - other.0)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::hash::DefaultHasher;
+    use super::*;
+
+    #[test]
+    fn nan_is_equal_to_itself_and_greater_than_every_other_float() {
+        let nan: Float = Float::new(f64::NAN);
+        assert_eq!(nan, Float::new(-f64::NAN));
+        assert!(nan > Float::new(f64::INFINITY));
+    }
+
+    #[test]
+    fn negative_zero_is_equal_to_zero() {
+        assert_eq!(Float::new(-0.0), Float::new(0.0));
+        assert_eq!(Float::new(-0.0).cmp(&Float::new(0.0)), Ordering::Equal);
+    }
+
+    #[test]
+    fn equal_floats_have_equal_hashes() {
+        let hash = |float: f64| {
+            let mut hasher: DefaultHasher = DefaultHasher::new();
+            Float::new(float).hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(-0.0), hash(0.0));
+        assert_eq!(hash(f64::NAN), hash(-f64::NAN));
+        assert_ne!(hash(1.0), hash(2.0));
+    }
+
+    #[test]
+    fn floats_are_written_in_scientific_notation() {
+        assert_eq!(Float::from_string("1500").unwrap().to_string(), "1.5e3");
+        assert_eq!(Float::new(f64::INFINITY).to_string(), "inf");
+    }
+
+}
@@ -1,14 +1,42 @@
 // Copyright Rob Gage 2025
 
 use crate::{
+    Instruction,
     Term,
     Value,
 };
+use smallvec::SmallVec;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{
+        DefaultHasher,
+        Hash,
+        Hasher,
+    },
+    iter::once,
+    mem::discriminant,
+    sync::Arc,
+};
 
 
 
-/// An environment that stores defined `Function`s
+/// An environment that stores defined `Function`s, and the `Instruction`s their bodies are lowered
+/// into
 pub struct Environment {
+    /// Whether stored function bodies are executed as the `Instruction`s they are lowered into,
+    /// rather than evaluated term by term
+    bytecode: bool,
+    /// The `Instruction` that each term in the term buffer is lowered into, at the same index
+    code: Vec<Instruction>,
+    /// The values pushed by `Instruction::Constant`
+    constants: Vec<Value>,
+    /// The `FunctionReference`s of stored lambda bodies grouped by the hashes of their bodies, so
+    /// that identical lambda bodies are only stored once
+    lambdas: HashMap<u64, Vec<FunctionReference>>,
+    /// The slices of the term buffer holding stored function bodies grouped by the hashes of
+    /// their bodies, so that functions with identical bodies share one slice
+    sequences: HashMap<u64, Vec<(usize, usize)>>,
     term_buffer: Vec<Term>,
     term_slices: Vec<(usize, usize)>,
 }
@@ -16,52 +44,184 @@ pub struct Environment {
 impl Environment {
 
     /// Creates a new `Environment`
-    pub const fn new() -> Self {
-        Self { term_buffer: Vec::new(), term_slices: Vec::new() }
+    pub fn new() -> Self {
+        Self {
+            bytecode: true,
+            code: Vec::new(),
+            constants: Vec::new(),
+            lambdas: HashMap::new(),
+            sequences: HashMap::new(),
+            term_buffer: Vec::new(),
+            term_slices: Vec::new(),
+        }
+    }
+
+    /// Lowers the terms stored since this `Environment` was last compiled into `Instruction`s,
+    /// which must be done once the bodies of the functions they apply have been set
+    pub fn compile(&mut self) {
+        for index in self.code.len()..self.term_buffer.len() {
+            let instruction: Instruction = match &self.term_buffer[index] {
+                Term::Application (reference) => Instruction::Call (*reference),
+                Term::Bind (slot, names) => Instruction::Bind (*slot as u32, names.len() as u32),
+                Term::Closure (..) | Term::Record (_) => Instruction::Term,
+                Term::Combinator (combinator) => Instruction::Combinator (*combinator),
+                Term::Data (Value::Boolean (boolean)) => Instruction::Boolean (*boolean),
+                Term::Data (value) => {
+                    self.constants.push(value.clone());
+                    Instruction::Constant ((self.constants.len() - 1) as u32)
+                }
+                Term::Local (slot) => Instruction::Local (*slot as u32),
+                Term::Recursion => Instruction::Recursion,
+                Term::Unbind (slot) => Instruction::Unbind (*slot as u32),
+            };
+            self.code.push(instruction);
+        }
     }
 
+    /// Returns the value at an index of the constant pool
+    pub fn constant(&self, index: usize) -> &Value { &self.constants[index] }
+
+    /// Returns whether stored function bodies are executed as the `Instruction`s they are lowered
+    /// into, rather than evaluated term by term
+    pub fn executes_bytecode(&self) -> bool { self.bytecode }
+
+    /// Returns the `Instruction` at an index of the code
+    pub fn instruction(&self, index: usize) -> Instruction { self.code[index] }
+
+    /// Sets whether stored function bodies are executed as the `Instruction`s they are lowered
+    /// into, or evaluated term by term, which has the same results but is slower
+    pub fn set_bytecode(&mut self, bytecode: bool) { self.bytecode = bytecode; }
+
+    /// Returns the `Term` at an index of the term buffer
+    pub fn term(&self, index: usize) -> &Term { &self.term_buffer[index] }
+
 }
 
 
 
+/// Hashes a function body, made of the kind of each of its terms along with the functions and
+/// combinators it applies and the values it pushes, so that bodies that may be identical can be
+/// found and then compared
+fn body_hash(body: &[Term]) -> u64 {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+    body.len().hash(&mut hasher);
+    for term in body {
+        discriminant(term).hash(&mut hasher);
+        match term {
+            Term::Application (reference) => reference.hash(&mut hasher),
+            Term::Combinator (combinator) => combinator.name().hash(&mut hasher),
+            Term::Data (value) => value.hash(&mut hasher),
+            _ => {}
+        }
+    }
+    hasher.finish()
+}
+
+
+
+/// The number of terms that can extend a borrowed or shared `Function` body without allocating
+const TAIL_SIZE: usize = 2;
+
+/// Terms that extend a borrowed or shared `Function` body
+type Tail = SmallVec<[Term; TAIL_SIZE]>;
+
 /// A `Function` that can be evaluated on a `VirtualMachine`
 #[derive(Clone)]
 pub enum Function<'a> {
-    Contiguous (&'a [Term]),
-    Composed (Vec<Term>)
+    /// A body borrowed from an `Environment` with the index of its first term there, followed by
+    /// terms that extend it
+    Contiguous (usize, &'a [Term], Tail),
+    /// A body built for a single evaluation
+    Composed (Vec<Term>),
+    /// A body shared with the lambda it was built for, so that it is only built once, followed by
+    /// terms that extend it
+    Shared (Arc<[Term]>, Tail),
 }
 
 impl<'a> Function<'_> {
 
-    /// Returns the body of this function as a slice of `Term`s
-    pub fn body(&self) -> &[Term] {
+    /// Extends this `Function`'s body with `Term`s, without copying the body it already has
+    pub fn extended(mut self, terms: impl Iterator<Item = Term>) -> Self {
+        match &mut self {
+            Self::Contiguous (_, _, tail) | Self::Shared (_, tail) => tail.extend(terms),
+            Self::Composed (body) => body.extend(terms),
+        }
+        self
+    }
+
+    /// Converts this `Function` into one that owns its body, so that it does not borrow from an
+    /// `Environment`
+    pub fn into_owned(self) -> Function<'static> {
         match self {
-            Self::Contiguous (terms) => terms,
-            Self::Composed (terms) => terms
+            Self::Contiguous (_, terms, tail) => Function::Composed (terms.iter().cloned()
+                .chain(tail)
+                .collect()),
+            Self::Composed (terms) => Function::Composed (terms),
+            Self::Shared (terms, tail) => Function::Shared (terms, tail),
         }
     }
-    
-    /// Extends this `Function`'s body with a slice of `Term`s
-    pub fn extended(self, terms: impl Iterator<Item = Term>) -> Self {
-        let mut body: Vec<Term> = self.body().to_vec();
-        body.extend(terms);
-        Self::Composed (body)
+
+    /// Returns the `Term` at an index of this `Function`'s body
+    pub fn term(&self, index: usize) -> Option<&Term> {
+        let (body, tail): (&[Term], &[Term]) = match self {
+            Self::Contiguous (_, body, tail) => (body, tail),
+            Self::Composed (body) => return body.get(index),
+            Self::Shared (body, tail) => (body, tail),
+        };
+        body.get(index).or_else(|| tail.get(index - body.len()))
+    }
+
+    /// Returns the `Term`s of this `Function`'s body in order
+    pub fn terms(&self) -> impl Iterator<Item = &Term> {
+        let (body, tail): (&[Term], &[Term]) = match self {
+            Self::Contiguous (_, body, tail) => (body, tail),
+            Self::Composed (body) => (body, &[]),
+            Self::Shared (body, tail) => (body, tail),
+        };
+        body.iter().chain(tail)
     }
-    
+
 }
 
 
 
 /// A reference to a `Function` in an `Environment`
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct FunctionReference (usize);
 
 impl FunctionReference {
 
+    /// Gets the body of the `Function` from its `Environment`
+    pub fn body<'a>(&self, environment: &'a Environment) -> &'a [Term] {
+        let (start, end): (usize, usize) = environment.term_slices[self.0];
+        &environment.term_buffer[start..end]
+    }
+
+    /// Gets the range of the term buffer holding the body of the function, which is also the range
+    /// of the code holding the `Instruction`s the body is lowered into
+    pub fn slice(&self, environment: &Environment) -> (usize, usize) {
+        environment.term_slices[self.0]
+    }
+
     /// Gets the `Function` from its `Environment`
     pub fn get<'a>(&self, environment: &'a Environment) -> Function<'a> {
-        let (start, end): (usize, usize) = environment.term_slices[self.0];
-        Function::Contiguous(&environment.term_buffer[start..end])
+        let (start, _): (usize, usize) = environment.term_slices[self.0];
+        Function::Contiguous (start, self.body(environment), Tail::new())
+    }
+
+    /// Stores a lambda body in an `Environment`, returning the `FunctionReference` of an identical
+    /// body instead if one has already been stored
+    pub fn intern(environment: &mut Environment, body: &[Term]) -> Self {
+        let hash: u64 = body_hash(body);
+        let stored: Option<Self> = environment.lambdas.get(&hash)
+            .and_then(|references| references.iter()
+                .find(|reference| reference.body(environment) == body)
+                .copied());
+        if let Some (reference) = stored { return reference; }
+        let reference: Self = Self::reserve(environment);
+        reference.set_body(environment, body);
+        environment.lambdas.entry(hash).or_default().push(reference);
+        reference
     }
 
     /// Reserves a `FunctionReference` in an `Environment`
@@ -70,11 +230,22 @@ impl FunctionReference {
         Self (environment.term_slices.len() - 1)
     }
 
-    /// Sets the body of this function using a slice of `Term`s
+    /// Sets the body of this function using a slice of `Term`s, sharing the stored slice of an
+    /// identical body if there is one
     pub fn set_body(&self, environment: &mut Environment, body: &[Term]) {
+        let hash: u64 = body_hash(body);
+        let stored: Option<(usize, usize)> = environment.sequences.get(&hash)
+            .and_then(|slices| slices.iter()
+                .find(|(start, end)| &environment.term_buffer[*start..*end] == body)
+                .copied());
+        if let Some (slice) = stored {
+            environment.term_slices[self.0] = slice;
+            return;
+        }
         let start: usize = environment.term_buffer.len();
         environment.term_buffer.extend_from_slice(body);
         let end: usize = environment.term_buffer.len();
+        environment.sequences.entry(hash).or_default().push((start, end));
         environment.term_slices[self.0] = (start, end);
     }
 
@@ -82,32 +253,215 @@ impl FunctionReference {
 
 
 
-/// Represents a function that is treated as data on the `VirtualMachine`
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct LambdaReference (Vec<usize>);
+/// Represents a function that is treated as data on the `VirtualMachine`, as the body it
+/// evaluates with the values it captured pushed before the body they were captured by
+///
+/// Lambdas are equal when their resolved bodies are equal, so lambda literals with identical
+/// bodies are equal, and so are lambdas composed or curried into the same body, such as
+/// `( 1 2 )` and `( 1 ) ( 2 ) compose`. Copies of a lambda share its body, so a lambda is only
+/// identical to its own copies.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct LambdaReference (Arc<Lambda>);
 
 impl LambdaReference {
 
     /// Gets the lambda as a `Function` from its `Environment`
     pub fn get<'a>(&self, environment: &'a Environment) -> Function<'a> {
-        let mut body: Vec<Term> = Vec::new();
-        for &index in &self.0 {
-            body.extend_from_slice(FunctionReference (index).get(environment).body());
+        match self.0.function {
+            Some (function_reference) => function_reference.get(environment),
+            None => Function::Shared (self.0.body.clone(), Tail::new()),
         }
-        Function::Composed (body)
     }
 
     /// Composes this lambda with another
-    pub fn compose(mut self, other: LambdaReference) -> Self {
-        self.0.extend(other.0);
-        self
+    pub fn compose(self, other: LambdaReference) -> Self {
+        Self::from_body(self.0.body.iter().chain(other.0.body.iter()).cloned().collect())
+    }
+
+    /// Captures a value in this lambda, so that it is pushed to the stack before the lambda's
+    /// body is evaluated
+    pub fn curry(self, value: Value) -> Self {
+        Self::from_body(once(Term::Data (value))
+            .chain(self.0.body.iter().cloned())
+            .collect())
+    }
+
+    /// Returns whether this lambda is identical to another, meaning that one is a copy of the
+    /// other, rather than an equal lambda created separately
+    pub fn is_identical(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+
+    /// Creates a new `LambdaReference` from a `FunctionReference` and the values it captures,
+    /// which are pushed to the stack before its body is evaluated
+    pub fn from_closure(
+        environment: &Environment,
+        function_reference: FunctionReference,
+        captured: Vec<Value>,
+    ) -> LambdaReference {
+        if captured.is_empty() { return Self::from_function(environment, function_reference); }
+        Self::from_body(captured.into_iter()
+            .map(Term::Data)
+            .chain(function_reference.body(environment).iter().cloned())
+            .collect())
     }
 
     /// Creates a new `LambdaReference` from a `FunctionReference`
     pub fn from_function(
-        function_reference: FunctionReference
+        environment: &Environment,
+        function_reference: FunctionReference,
     ) -> LambdaReference {
-        Self (vec![function_reference.0])
+        Self (Arc::new(Lambda {
+            body: function_reference.body(environment).into(),
+            function: Some (function_reference),
+        }))
     }
 
-}
\ No newline at end of file
+    /// Creates a new `LambdaReference` from a body that is not stored in an `Environment`
+    fn from_body(body: Arc<[Term]>) -> LambdaReference {
+        Self (Arc::new(Lambda { body, function: None }))
+    }
+
+}
+
+
+
+/// The resolved body of a `LambdaReference`, and the function stored in an `Environment` with that
+/// body if the lambda is one, which is not part of the lambda's equality or ordering
+#[derive(Debug)]
+struct Lambda {
+    /// The body of the lambda, including its captured values
+    body: Arc<[Term]>,
+    /// The stored function that the lambda applies, so that its lowered `Instruction`s can be
+    /// executed, if the lambda is exactly one stored function without captured values
+    function: Option<FunctionReference>,
+}
+
+impl Eq for Lambda {}
+
+impl Ord for Lambda {
+
+    fn cmp(&self, other: &Self) -> Ordering { self.body.cmp(&other.body) }
+
+}
+
+impl PartialEq for Lambda {
+
+    fn eq(&self, other: &Self) -> bool { self.body == other.body }
+
+}
+
+impl PartialOrd for Lambda {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::{
+        body_hash,
+        Environment,
+        Function,
+        FunctionReference,
+        LambdaReference,
+    };
+    use crate::{
+        Combinator,
+        Integer,
+        Term,
+        Value,
+    };
+    use std::sync::Arc;
+
+    fn body(integer: usize) -> Vec<Term> {
+        vec![
+            Term::Data (Value::Integer (Integer::from_usize(integer))),
+            Term::Combinator (Combinator::Add),
+        ]
+    }
+
+    #[test]
+    fn identical_bodies_share_one_slice() {
+        let mut environment: Environment = Environment::new();
+        let first: FunctionReference = FunctionReference::reserve(&mut environment);
+        let second: FunctionReference = FunctionReference::reserve(&mut environment);
+        first.set_body(&mut environment, &body(1));
+        second.set_body(&mut environment, &body(1));
+        assert_eq!(environment.term_slices[first.0], environment.term_slices[second.0]);
+        assert_eq!(environment.term_buffer.len(), 2);
+    }
+
+    #[test]
+    fn bodies_that_push_different_values_are_hashed_and_stored_separately() {
+        let mut environment: Environment = Environment::new();
+        let first: FunctionReference = FunctionReference::reserve(&mut environment);
+        let second: FunctionReference = FunctionReference::reserve(&mut environment);
+        first.set_body(&mut environment, &body(1));
+        second.set_body(&mut environment, &body(2));
+        assert_ne!(environment.term_slices[first.0], environment.term_slices[second.0]);
+        assert_ne!(body_hash(&body(1)), body_hash(&body(2)));
+        assert_eq!(environment.sequences.len(), 2);
+        assert_eq!(first.body(&environment), body(1).as_slice());
+        assert_eq!(second.body(&environment), body(2).as_slice());
+    }
+
+    #[test]
+    fn identical_lambda_bodies_are_interned_once() {
+        let mut environment: Environment = Environment::new();
+        let first: FunctionReference = FunctionReference::intern(&mut environment, &body(1));
+        let second: FunctionReference = FunctionReference::intern(&mut environment, &body(1));
+        let third: FunctionReference = FunctionReference::intern(&mut environment, &body(2));
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+        assert_eq!(environment.term_slices.len(), 2);
+    }
+
+
+    #[test]
+    fn bodies_that_differ_only_in_the_names_of_their_locals_are_interned_once() {
+        let mut environment: Environment = Environment::new();
+        let bound = |name: &str| vec![
+            Term::Bind (0, [name.to_string()].into_iter().collect()),
+            Term::Local (0),
+            Term::Unbind (0),
+        ];
+        let first: FunctionReference = FunctionReference::intern(&mut environment, &bound("a"));
+        let second: FunctionReference = FunctionReference::intern(&mut environment, &bound("b"));
+        assert_eq!(first, second);
+        assert_eq!(environment.term_slices.len(), 1);
+    }
+
+    #[test]
+    fn extended_bodies_keep_a_small_tail_without_copying_the_body() {
+        let mut environment: Environment = Environment::new();
+        let reference: FunctionReference = FunctionReference::intern(&mut environment, &body(1));
+        let extended: Function = reference.get(&environment)
+            .extended(body(2).into_iter());
+        let Function::Contiguous (_, borrowed, tail) = &extended else {
+            panic!("Extending a stored body does not borrow it")
+        };
+        assert!(std::ptr::eq(*borrowed, reference.body(&environment)));
+        assert!(!tail.spilled());
+        let terms: Vec<Term> = body(1).into_iter().chain(body(2)).collect();
+        assert!(extended.terms().eq(terms.iter()));
+        assert_eq!(extended.term(3), Some (&terms[3]));
+        assert_eq!(extended.term(4), None);
+    }
+
+    #[test]
+    fn composed_lambdas_share_their_body_between_applications() {
+        let mut environment: Environment = Environment::new();
+        let reference: FunctionReference = FunctionReference::intern(&mut environment, &body(1));
+        let lambda: LambdaReference = LambdaReference::from_function(&environment, reference);
+        assert!(matches!(lambda.clone().get(&environment), Function::Contiguous (..)));
+        let composed: LambdaReference = lambda.clone().compose(lambda);
+        let (Function::Shared (first, _), Function::Shared (second, _))
+            = (composed.get(&environment), composed.get(&environment))
+        else { panic!("Composed lambdas do not share their body") };
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.len(), 4);
+    }
+
+}
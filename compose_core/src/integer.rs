@@ -1,23 +1,119 @@
 // Copyright Rob Gage 2025
 
-use num_bigint::BigInt;
-use num_traits::ToPrimitive;
+use num_bigint::{
+    BigInt,
+    Sign,
+};
+use crate::Float;
+use num_integer::Integer as _;
+use num_traits::{
+    FromPrimitive,
+    Pow,
+    Signed,
+    ToPrimitive,
+    Zero,
+};
 use std::{
     ops::{
         Add,
+        BitAnd,
+        BitOr,
+        BitXor,
         Div,
         Mul,
+        Neg,
+        Not,
         Rem,
+        Shl,
+        Shr,
         Sub
     },
     str::FromStr
 };
 
-#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Integer (BigInt);
+/// The greatest number of bits that an `Integer` made by an operation that could otherwise
+/// exhaust memory, such as exponentiation or shifting, is allowed to have
+const MAXIMUM_BITS: u64 = 1 << 24;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Integer (pub(crate) BigInt);
 
 impl Integer {
 
+    /// Returns the absolute value of this `Integer`
+    pub fn abs(&self) -> Self { Self (self.0.abs()) }
+
+    /// Returns the number of bits needed to represent the absolute value of this `Integer`
+    pub fn bit_length(&self) -> Self { Self (BigInt::from(self.0.bits())) }
+
+    /// Divides this `Integer` by another, rounding the quotient toward negative infinity
+    pub fn div_floor(&self, divisor: &Self) -> Self { Self (self.0.div_floor(&divisor.0)) }
+
+    /// Returns the greatest common divisor of this `Integer` and another, which is never negative
+    pub fn gcd(&self, other: &Self) -> Self { Self (self.0.gcd(&other.0)) }
+
+    /// Returns whether this `Integer` is less than zero
+    pub fn is_negative(&self) -> bool { self.0.is_negative() }
+
+    /// Returns whether this `Integer` is zero
+    pub fn is_zero(&self) -> bool { self.0.is_zero() }
+
+    /// Returns the greatest `Integer` whose square is less than or equal to this `Integer`, or
+    /// `None` if this `Integer` is negative
+    pub fn isqrt(&self) -> Option<Self> {
+        if self.0.is_negative() { None } else { Some (Self (self.0.sqrt())) }
+    }
+
+    /// Returns the least common multiple of this `Integer` and another, which is never negative
+    pub fn lcm(&self, other: &Self) -> Self { Self (self.0.lcm(&other.0)) }
+
+    /// Returns the remainder of dividing this `Integer` by another with the quotient rounded
+    /// toward negative infinity, which has the same sign as the divisor
+    pub fn mod_floor(&self, divisor: &Self) -> Self { Self (self.0.mod_floor(&divisor.0)) }
+
+    /// Raises this `Integer` to a power modulo another `Integer`, returning `None` if the
+    /// exponent is negative or the modulus is zero
+    pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Option<Self> {
+        if exponent.0.is_negative() || modulus.0.is_zero() { return None; }
+        Some (Self (self.0.modpow(&exponent.0, &modulus.0)))
+    }
+
+    /// Raises this `Integer` to a non-negative power, returning `None` if the exponent is negative
+    /// or the result would have more than `MAXIMUM_BITS` bits
+    pub fn pow(&self, exponent: &Self) -> Option<Self> {
+        if exponent.0.is_negative() { return None; }
+        // zero, one and negative one never grow, however large the exponent is
+        if self.0.bits() <= 1 {
+            let one: bool = exponent.0.is_zero() || (self.0.is_negative() && exponent.0.is_even());
+            return Some (if one { Self (BigInt::from(1)) } else { self.clone() });
+        }
+        let exponent: u64 = exponent.0.to_u64()?;
+        if self.0.bits().checked_mul(exponent)? > MAXIMUM_BITS { return None; }
+        Some (Self (Pow::pow(&self.0, exponent)))
+    }
+
+    /// Shifts the bits of this `Integer` left, returning `None` if the shift is negative or the
+    /// result would have more than `MAXIMUM_BITS` bits
+    pub fn shifted_left(&self, shift: &Self) -> Option<Self> {
+        let shift: u64 = shift.0.to_u64()?;
+        if self.0.is_zero() { return Some (self.clone()); }
+        if self.0.bits().checked_add(shift)? > MAXIMUM_BITS { return None; }
+        Some (Self ((&self.0).shl(shift)))
+    }
+
+    /// Shifts the bits of this `Integer` right, rounding toward negative infinity, or returns
+    /// `None` if the shift is negative
+    pub fn shifted_right(&self, shift: &Self) -> Option<Self> {
+        if shift.0.is_negative() { return None; }
+        match shift.0.to_u64() {
+            Some (shift) if shift < self.0.bits() => Some (Self ((&self.0).shr(shift))),
+            _ => Some (Self (BigInt::from(if self.0.is_negative() { -1 } else { 0 }))),
+        }
+    }
+
+    /// Returns -1, 0 or 1 depending on whether this `Integer` is negative, zero or positive
+    pub fn signum(&self) -> Self { Self (self.0.signum()) }
+
     /// Create an `Integer` from a string slice
     pub fn from_string(string: &str) -> Option<Self> {
         BigInt::from_str(string) // parse rug integer from string
@@ -25,6 +121,51 @@ impl Integer {
             .map(Self) // apply `Integer` constructor
     }
 
+    /// Creates a non-negative `Integer` from bytes in big-endian or little-endian order
+    pub fn from_bytes(bytes: &[u8], big_endian: bool) -> Self {
+        Self (if big_endian {
+            BigInt::from_bytes_be(Sign::Plus, bytes)
+        } else { BigInt::from_bytes_le(Sign::Plus, bytes) })
+    }
+
+    /// Creates an `Integer` from a `Float`, truncating it toward zero, or returns `None` if the
+    /// `Float` is infinite or NaN
+    pub fn from_float(float: Float) -> Option<Self> {
+        BigInt::from_f64(float.value().trunc()).map(Self)
+    }
+
+    /// Creates an `Integer` from a literal with an optional sign, an optional `0x`, `0o` or `0b`
+    /// radix prefix and underscores between its digits, such as `-0xFF_FF`, or returns a message
+    /// describing why the literal is malformed
+    pub fn from_literal(literal: &str) -> Result<Self, String> {
+        let malformed =
+            |reason: &str| format!("Malformed integer literal `{}`: {}", literal, reason);
+        let (negative, unsigned): (bool, &str) = match literal.strip_prefix('-') {
+            Some (unsigned) => (true, unsigned),
+            None => (false, literal),
+        };
+        let (radix, radix_name, digits): (u32, &str, &str) = match unsigned.get(..2) {
+            Some ("0x" | "0X") => (16, "hexadecimal", &unsigned[2..]),
+            Some ("0o" | "0O") => (8, "octal", &unsigned[2..]),
+            Some ("0b" | "0B") => (2, "binary", &unsigned[2..]),
+            _ => (10, "decimal", unsigned),
+        };
+        if digits.is_empty() { return Err (malformed("it has no digits")); }
+        if let Some (invalid) = digits.chars().find(|c| *c != '_' && !c.is_digit(radix)) {
+            return Err (malformed(&format!("`{}` is not a {} digit", invalid, radix_name)));
+        }
+        if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+            return Err (malformed("underscores can only be placed between digits"));
+        }
+        let digits: String = digits.chars().filter(|c| *c != '_').collect();
+        let magnitude: BigInt = BigInt::parse_bytes(digits.as_bytes(), radix)
+            .expect("Digits have already been checked to be valid");
+        Ok (Self (if negative { -magnitude } else { magnitude }))
+    }
+
+    /// Creates a new `Integer` from an `isize`
+    pub fn from_isize(isize: isize) -> Self { Self (BigInt::from(isize)) }
+
     /// Creates a new `Integer` from a `usize`
     pub fn from_usize(usize: usize) -> Self { Self (BigInt::from(usize)) }
 
@@ -33,6 +174,56 @@ impl Integer {
         self.0.to_string()
     }
 
+    /// Returns this `Integer` as a byte, or returns `None` if it is not from 0 to 255
+    pub fn to_byte(&self) -> Option<u8> { self.0.to_u8() }
+
+    /// Returns this `Integer` as exactly `width` bytes in big-endian or little-endian order, or
+    /// returns `None` if it is negative or does not fit in that many bytes
+    pub fn to_bytes(&self, width: usize, big_endian: bool) -> Option<Vec<u8>> {
+        if self.0.is_negative() { return None; }
+        let mut bytes: Vec<u8> = self.0.magnitude().to_bytes_le();
+        if self.0.is_zero() { bytes.clear(); }
+        if bytes.len() > width { return None; }
+        bytes.resize(width, 0);
+        if big_endian { bytes.reverse(); }
+        Some (bytes)
+    }
+
+    /// Returns the `Integer` as a literal in a radix with its prefix, such as `0xff` for radix 16,
+    /// where radixes other than 2, 8 and 16 are written in decimal
+    pub fn to_literal(&self, radix: u32) -> String {
+        let prefix: &str = match radix {
+            16 => "0x",
+            8 => "0o",
+            2 => "0b",
+            _ => return self.0.to_string(),
+        };
+        let sign: &str = if self.0.is_negative() { "-" } else { "" };
+        format!("{}{}{}", sign, prefix, self.0.magnitude().to_str_radix(radix))
+    }
+
+    /// Returns the nearest `Float` to this `Integer`, which is infinite if it is too large
+    pub fn to_float(&self) -> Float {
+        Float::new(self.0.to_f64().unwrap_or(f64::NAN))
+    }
+
+    /// Returns this `Integer` as a `usize`, or returns `None` if it is negative or too large
+    pub fn to_usize(&self) -> Option<usize> { self.0.to_usize() }
+
+    /// Returns this `Integer` as a number of repetitions, treating negative numbers as zero and
+    /// saturating numbers too large for a `usize`
+    pub fn as_count(&self) -> usize {
+        if self.0.sign() == Sign::Minus { 0 } else { self.0.to_usize().unwrap_or(usize::MAX) }
+    }
+
+    /// Returns this `Integer` as a bound of a slice of a list with a given length, counting from
+    /// the end of the list if it is negative, and clamping it to the bounds of the list
+    pub fn as_slice_bound(&self, length: usize) -> usize {
+        let length: BigInt = BigInt::from(length);
+        let bound: BigInt = if self.0.is_negative() { &length + &self.0 } else { self.0.clone() };
+        bound.clamp(BigInt::zero(), length).to_usize().unwrap_or(0)
+    }
+
     /// Returns this `Integer` as a `usize` wrapping index for an item in a stack or list with a
     /// given size, returning `usize::MAX` as a sentinel value indicating an empty space
     pub fn as_wrapping_index(&self, stack_size: usize) -> usize {
@@ -52,6 +243,30 @@ impl Add for Integer {
     }
 }
 
+impl BitAnd for Integer {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        Self (self.0 & other.0)
+    }
+}
+
+impl BitOr for Integer {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        Self (self.0 | other.0)
+    }
+}
+
+impl BitXor for Integer {
+    type Output = Self;
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self (self.0 ^ other.0)
+    }
+}
+
 impl Div for Integer {
     type Output = Self;
     #[inline]
@@ -68,6 +283,22 @@ impl Mul for Integer {
     }
 }
 
+impl Neg for Integer {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self (-self.0)
+    }
+}
+
+impl Not for Integer {
+    type Output = Self;
+    #[inline]
+    fn not(self) -> Self {
+        Self (!self.0)
+    }
+}
+
 impl Rem for Integer {
     type Output = Self;
     #[inline]
@@ -82,4 +313,86 @@ impl Sub for Integer {
     fn sub(self, other: Self) -> Self {
         Self (self.0 - other.0)
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn powers_that_would_be_too_large_are_refused() {
+        let two: Integer = Integer::from_isize(2);
+        assert_eq!(two.pow(&Integer::from_isize(10)), Some (Integer::from_isize(1024)));
+        assert_eq!(two.pow(&Integer::from_isize(-1)), None);
+        assert_eq!(two.pow(&Integer::from_usize(MAXIMUM_BITS as usize)), None);
+    }
+
+    #[test]
+    fn powers_of_zero_and_ones_never_grow() {
+        let huge: Integer = Integer::from_string("100000000000000000000").unwrap();
+        assert_eq!(Integer::from_isize(1).pow(&huge), Some (Integer::from_isize(1)));
+        assert_eq!(Integer::from_isize(-1).pow(&huge), Some (Integer::from_isize(1)));
+        assert_eq!(Integer::from_isize(0).pow(&huge), Some (Integer::from_isize(0)));
+        let zero: Integer = Integer::from_isize(0);
+        assert_eq!(zero.pow(&zero), Some (Integer::from_isize(1)));
+    }
+
+    #[test]
+    fn modular_powers_need_a_non_negative_exponent_and_a_nonzero_modulus() {
+        let three: Integer = Integer::from_isize(3);
+        let seven: Integer = Integer::from_isize(7);
+        assert_eq!(three.modpow(&Integer::from_isize(200), &seven), Some (Integer::from_isize(2)));
+        assert_eq!(three.modpow(&Integer::from_isize(-1), &seven), None);
+        assert_eq!(three.modpow(&three, &Integer::from_isize(0)), None);
+    }
+
+    #[test]
+    fn shifts_round_toward_negative_infinity_and_refuse_huge_results() {
+        let one: Integer = Integer::from_isize(1);
+        assert_eq!(one.shifted_left(&Integer::from_isize(10)), Some (Integer::from_isize(1024)));
+        assert_eq!(one.shifted_left(&Integer::from_isize(-1)), None);
+        assert_eq!(one.shifted_left(&Integer::from_usize(MAXIMUM_BITS as usize)), None);
+        assert_eq!(Integer::from_isize(-1).shifted_right(&one), Some (Integer::from_isize(-1)));
+        assert_eq!(Integer::from_isize(-5).shifted_right(&one), Some (Integer::from_isize(-3)));
+    }
+
+    #[test]
+    fn literals_are_parsed_in_their_radix() {
+        let literal = |literal: &str| Integer::from_literal(literal).unwrap().to_string();
+        assert_eq!(literal("0xFF_FF"), "65535");
+        assert_eq!(literal("0o17"), "15");
+        assert_eq!(literal("0b1010"), "10");
+        assert_eq!(literal("1_000_000"), "1000000");
+        assert_eq!(literal("-0x10"), "-16");
+    }
+
+    #[test]
+    fn malformed_literals_are_described() {
+        let error = |literal: &str| Integer::from_literal(literal).unwrap_err();
+        assert_eq!(error("0x"), "Malformed integer literal `0x`: it has no digits");
+        assert_eq!(
+            error("0xZZ"),
+            "Malformed integer literal `0xZZ`: `Z` is not a hexadecimal digit"
+        );
+        assert_eq!(error("0b12"), "Malformed integer literal `0b12`: `2` is not a binary digit");
+        assert_eq!(
+            error("1__0"),
+            "Malformed integer literal `1__0`: underscores can only be placed between digits"
+        );
+        assert_eq!(
+            error("10_"),
+            "Malformed integer literal `10_`: underscores can only be placed between digits"
+        );
+    }
+
+    #[test]
+    fn literals_are_written_with_a_radix_prefix() {
+        assert_eq!(Integer::from_isize(255).to_literal(16), "0xff");
+        assert_eq!(Integer::from_isize(-8).to_literal(8), "-0o10");
+        assert_eq!(Integer::from_isize(5).to_literal(2), "0b101");
+        assert_eq!(Integer::from_isize(255).to_literal(10), "255");
+    }
+
+}
@@ -1,10 +1,15 @@
 // Copyright Rob Gage 2025
 
+mod encoding;
+mod float;
 mod functions;
 mod integer;
 mod namespace;
+mod rational;
 mod syntax;
 mod term;
+#[cfg(test)]
+mod testing;
 mod virtual_machine;
 
 use functions::{
@@ -12,16 +17,37 @@ use functions::{
     Function,
     LambdaReference,
 };
-use virtual_machine::Combinator;
+use virtual_machine::{
+    Combinator,
+    Instruction,
+    RecordOperation,
+};
+use float::Float;
 use integer::Integer;
+use rational::Rational;
 use syntax::UnresolvedTerm;
 use term::Term;
 
 pub use virtual_machine::{
+    Record,
+    RecordType,
+    Sequence,
+    UnionType,
     Value,
     VirtualMachine,
 };
 
 pub use functions::FunctionReference;
-pub use namespace::Namespace;
-pub use syntax::UnresolvedFunction;
\ No newline at end of file
+pub use namespace::{
+    LoadError,
+    Namespace,
+};
+pub use syntax::{
+    literal_errors,
+    DeclarationError,
+    UnresolvedFunction,
+    UnresolvedImport,
+    UnresolvedModule,
+    UnresolvedRecord,
+    UnresolvedUnion,
+};
\ No newline at end of file
@@ -1,7 +1,12 @@
 // Copyright Rob Gage 2025
 
+mod load_error;
+
+use pups::*;
 use std::{
     collections::{
+        BTreeMap,
+        BTreeSet,
         HashMap,
         HashSet,
     },
@@ -9,12 +14,18 @@ use std::{
         Result as FormatResult,
         Write,
     },
+    fs::read_to_string,
+    path::{
+        Path,
+        PathBuf,
+    },
     sync::{
         Arc,
         RwLock,
     }
 };
 use crate::{
+    Combinator,
     Value,
     Environment,
     FunctionReference,
@@ -22,8 +33,21 @@ use crate::{
     Term,
     VirtualMachine,
     UnresolvedFunction,
-    UnresolvedTerm
+    UnresolvedModule,
+    UnresolvedTerm,
+    syntax::is_reserved_word,
 };
+
+pub use load_error::LoadError;
+
+/// The standard prelude of functions written in Compose
+const PRELUDE: &str = include_str!("prelude.cmp");
+
+/// The name of the module that the standard prelude is registered as
+const PRELUDE_MODULE: &str = "prelude";
+
+/// The file extension of Compose source files
+const SOURCE_EXTENSION: &str = "cmp";
 use crate::Value::Lambda;
 
 /// Allows definition and retrieval of named functions and anonymous functions
@@ -32,28 +56,89 @@ pub struct Namespace {
     environment: Arc<RwLock<Environment>>,
     /// The indices of defined functions in the function storage mapped by name
     functions_by_name: HashMap<String, FunctionReference>,
+    /// The radix that integers are written in when values are displayed
+    integer_radix: u32,
+    /// The functions defined by each loaded module mapped by their unqualified names, mapped by
+    /// module name
+    modules: HashMap<String, HashMap<String, FunctionReference>>,
     /// The names of functions defined in the `Namespace` mapped by their function index
-    names_by_function: HashMap<FunctionReference, String>
+    names_by_function: HashMap<FunctionReference, String>,
+    /// The directories that are searched for module files
+    search_path: Vec<PathBuf>,
 }
 
 impl Namespace {
 
+    /// Adds a directory to the search path used to locate module files
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) {
+        self.search_path.push(path.into());
+    }
+
     /// Creates a new `VirtualMachine` from this `Namespace`
     pub fn create_virtual_machine(&self) -> VirtualMachine {
         VirtualMachine::from_environment(&self.environment)
     }
 
-    /// Defines a new `Function` in this `Namespace` from an `UnresolvedFunction`
+    /// Defines a new `Function` in this `Namespace` from an `UnresolvedFunction`, failing if its
+    /// name is a reserved word or it uses functions that are not defined
     pub fn define(
         &mut self,
         unresolved_function: &UnresolvedFunction,
-    ) -> Result<FunctionReference, HashSet<String>> {
+    ) -> Result<FunctionReference, LoadError> {
+        if is_reserved_word(unresolved_function.name()) {
+            return Err (LoadError::Conflict (unresolved_function.name().to_string()));
+        }
         let environment: &mut Environment = &mut *self.environment.write().unwrap();
         let reference: FunctionReference = FunctionReference::reserve(environment);
         self.functions_by_name.insert(unresolved_function.name().to_string(), reference);
         self.names_by_function.insert(reference, unresolved_function.name().to_string());
-        resolve(environment, &self.functions_by_name, reference, unresolved_function.body())?;
-        Ok (reference)
+        let resolved: Result<(), HashSet<String>>
+            = resolve(environment, &self.functions_by_name, reference, unresolved_function.body());
+        environment.compile();
+        resolved.map(|_| reference).map_err(LoadError::Undefined)
+    }
+
+    /// Imports a module into the root scope of this `Namespace`, loading it from the search path
+    /// if it has not already been loaded
+    pub fn import(&mut self, module_name: &str) -> Result<(), LoadError> {
+        let mut scope: HashMap<String, FunctionReference> = self.functions_by_name.clone();
+        self.import_into(&mut scope, &mut HashSet::new(), module_name, false, &mut Vec::new())?;
+        self.functions_by_name.extend(scope);
+        Ok (())
+    }
+
+    /// Loads a module from the search path if it has not already been loaded, without bringing
+    /// the unqualified names of its functions into the root scope of this `Namespace`
+    pub fn import_qualified(&mut self, module_name: &str) -> Result<(), LoadError> {
+        let mut scope: HashMap<String, FunctionReference> = self.functions_by_name.clone();
+        self.import_into(&mut scope, &mut HashSet::new(), module_name, true, &mut Vec::new())?;
+        self.functions_by_name.extend(scope);
+        Ok (())
+    }
+
+    /// Loads a source file, registering it as a module if it declares one, and otherwise defining
+    /// its functions in the root scope of this `Namespace`, where they can only replace functions
+    /// of the standard prelude
+    pub fn load(&mut self, path: &Path) -> Result<(), LoadError> {
+        let module: UnresolvedModule = read_module(path)?;
+        if let Some (module_name) = module.name() {
+            if self.modules.contains_key(module_name) {
+                return Err (LoadError::Conflict (module_name.to_string()));
+            }
+            self.define_module(module_name, &module, &mut Vec::new())
+        } else {
+            for function in module.functions() {
+                let name: &str = function.name();
+                if self.functions_by_name.get(name)
+                    .is_some_and(|existing| !self.is_prelude(name, *existing)) {
+                    return Err (LoadError::Conflict (name.to_string()));
+                }
+            }
+            let mut scope: HashMap<String, FunctionReference> = self.functions_by_name.clone();
+            self.define_functions(&mut scope, None, &module, &mut Vec::new())?;
+            self.functions_by_name.extend(scope);
+            Ok (())
+        }
     }
 
     /// Creates a new `Namespace`
@@ -61,10 +146,38 @@ impl Namespace {
         Self {
             environment: Arc::new(RwLock::new(Environment::new())),
             functions_by_name: HashMap::new(),
-            names_by_function: HashMap::new()
+            integer_radix: 10,
+            modules: HashMap::new(),
+            names_by_function: HashMap::new(),
+            search_path: Vec::new(),
         }
     }
 
+    /// Sets the radix that integers are written in when values are displayed, which can be 2, 8 or
+    /// 16 to write them as prefixed literals such as `0xff`, or anything else to write them in
+    /// decimal
+    pub fn set_integer_radix(&mut self, radix: u32) {
+        self.integer_radix = radix;
+    }
+
+    /// Sets whether the functions in this `Namespace` are executed as the `Instruction`s they are
+    /// lowered into, or evaluated term by term, which has the same results but is slower
+    pub fn set_bytecode(&mut self, bytecode: bool) {
+        self.environment.write().unwrap().set_bytecode(bytecode);
+    }
+
+    /// Creates a new `Namespace` with the standard prelude imported into its root scope
+    pub fn with_prelude() -> Self {
+        let mut namespace: Self = Self::new();
+        let prelude: UnresolvedModule = parse_module(Path::new("prelude.cmp"), PRELUDE)
+            .expect("The prelude is valid Compose source");
+        namespace.define_module(PRELUDE_MODULE, &prelude, &mut Vec::new())
+            .expect("The prelude only uses functions it defines");
+        namespace.import(PRELUDE_MODULE)
+            .expect("The prelude can be imported into an empty `Namespace`");
+        namespace
+    }
+
     /// Displays a `Term` within the context of this `Namespace`
     pub fn write_term<W: Write>(&self, w: &mut W, term: &Term) -> FormatResult {
         match term {
@@ -73,9 +186,31 @@ impl Namespace {
                     .expect("`Namespace::format_term` will only be called on terms that exist \
                    in this `Namespace`")
             ),
+            Term::Bind (_, names) => {
+                w.write_str("[ ")?;
+                for name in names.iter() {
+                    write!(w, "{} ", name)?;
+                }
+                w.write_str("] -> (")
+            },
+            // closures are written as they were in source, without the binding of their captures
+            Term::Closure (reference, _) => {
+                let environment = self.environment.read().unwrap();
+                let body: &[Term] = reference.body(&environment);
+                let names: &[String] = match body.first() {
+                    Some (Term::Bind (_, names)) => names,
+                    _ => &[],
+                };
+                w.write_str("( ")?;
+                self.write_terms(w, body.iter().skip(1).take(body.len().saturating_sub(2)), names)?;
+                w.write_char(')')
+            },
             Term::Combinator (combinator) => w.write_str(combinator.name()),
             Term::Data (value) => self.write_value(w, value),
+            Term::Local (slot) => write!(w, "${}", slot),
+            Term::Record (operation) => w.write_str(&operation.name()),
             Term::Recursion => w.write_str("@"),
+            Term::Unbind (_) => w.write_char(')'),
         }
     }
 
@@ -83,13 +218,21 @@ impl Namespace {
     pub fn write_value<W: Write>(&self, w: &mut W, value: &Value) -> FormatResult {
         match value {
             Value::Boolean (boolean) => w.write_str(if *boolean { "true" } else { "false" }),
-            Value::Integer (integer) => w.write_str(&integer.to_string()),
+            Value::Bytes (bytes) => {
+                w.write_str("#[ ")?;
+                for byte in bytes {
+                    write!(w, "{:02x} ", byte)?;
+                }
+                w.write_char(']')
+            }
+            Value::Continuation (_) => w.write_str("continuation"),
+            Value::Error (message) => write!(w, "error({:?})", message),
+            Value::Float (float) => w.write_str(&float.to_string()),
+            Value::Integer (integer) => w.write_str(&integer.to_literal(self.integer_radix)),
             Value::Lambda (reference) => {
+                let environment = self.environment.read().unwrap();
                 w.write_str("( ")?;
-                for term in reference.get(&*self.environment.read().unwrap()).body() {
-                    self.write_term(w, term)?;
-                    w.write_char(' ')?;
-                }
+                self.write_terms(w, reference.get(&environment).terms(), &[])?;
                 w.write_char(')')
             },
             Value::List (items) => {
@@ -100,9 +243,213 @@ impl Namespace {
                 }
                 w.write_char(']')
             }
+            Value::Map (entries) => {
+                w.write_str("{ ")?;
+                for (key, value) in entries {
+                    self.write_value(w, key)?;
+                    w.write_char(' ')?;
+                    self.write_value(w, value)?;
+                    w.write_char(' ')?;
+                }
+                w.write_char('}')
+            }
+            Value::Rational (rational) => w.write_str(&rational.to_string()),
+            Value::Record (record) if record.kind().fields().is_empty() =>
+                w.write_str(record.kind().name()),
+            Value::Record (record) => {
+                write!(w, "{} {{ ", record.kind().name())?;
+                for (field, value) in record.fields() {
+                    write!(w, "{}: ", field)?;
+                    self.write_value(w, value)?;
+                    w.write_char(' ')?;
+                }
+                w.write_char('}')
+            }
+            Value::Sequence (_) => w.write_str("sequence"),
         }
     }
 
+    /// Displays `Term`s within the context of this `Namespace` followed by spaces, writing local
+    /// slots with the names they are bound to, starting with names bound to the first slots
+    fn write_terms<'a, W: Write>(
+        &self,
+        w: &mut W,
+        terms: impl Iterator<Item = &'a Term>,
+        names: &[String],
+    ) -> FormatResult {
+        let mut locals: Vec<&str> = names.iter().map(String::as_str).collect();
+        for term in terms {
+            match term {
+                Term::Bind (slot, names) => {
+                    locals.truncate(*slot);
+                    locals.extend(names.iter().map(String::as_str));
+                    self.write_term(w, term)?;
+                }
+                Term::Local (slot) if *slot < locals.len() => w.write_str(locals[*slot])?,
+                term => self.write_term(w, term)?,
+            }
+            w.write_char(' ')?;
+        }
+        Ok (())
+    }
+
+    /// Defines the functions of an `UnresolvedModule` in a scope after importing the modules it
+    /// depends on, and returns the functions it defined mapped by their unqualified names
+    fn define_functions(
+        &mut self,
+        scope: &mut HashMap<String, FunctionReference>,
+        module_name: Option<&str>,
+        module: &UnresolvedModule,
+        loading: &mut Vec<String>,
+    ) -> Result<HashMap<String, FunctionReference>, LoadError> {
+        let mut imported: HashSet<String> = HashSet::new();
+        for import in module.imports() {
+            let module_name: &str = import.module_name();
+            self.import_into(scope, &mut imported, module_name, import.is_qualified(), loading)?;
+        }
+        let qualify = |name: &str| match module_name {
+            Some (module_name) => format!("{}.{}", module_name, name),
+            None => name.to_string(),
+        };
+        let environment: &mut Environment = &mut self.environment.write().unwrap();
+        // reserve every function first so that definitions can refer to each other in any order
+        let mut defined: HashMap<String, FunctionReference> = HashMap::new();
+        for function in module.functions() {
+            if defined.contains_key(function.name())
+                || imported.contains(function.name())
+                || is_reserved_word(function.name()) {
+                return Err (LoadError::Conflict (qualify(function.name())));
+            }
+            defined.insert(function.name().to_string(), FunctionReference::reserve(environment));
+        }
+        for (name, reference) in &defined {
+            scope.insert(name.clone(), *reference);
+            scope.insert(qualify(name), *reference);
+        }
+        let mut undefined: HashSet<String> = HashSet::new();
+        for function in module.functions() {
+            let reference: FunctionReference = defined[function.name()];
+            if let Err (missing) = resolve(environment, scope, reference, function.body()) {
+                undefined.extend(missing);
+            }
+        }
+        environment.compile();
+        if !undefined.is_empty() { return Err (LoadError::Undefined (undefined)); }
+        for (name, reference) in &defined {
+            self.names_by_function.insert(*reference, qualify(name));
+        }
+        Ok (defined)
+    }
+
+    /// Defines the functions of an `UnresolvedModule` in their own scope, and registers them as a
+    /// module whose qualified names are available in the root scope
+    fn define_module(
+        &mut self,
+        module_name: &str,
+        module: &UnresolvedModule,
+        loading: &mut Vec<String>,
+    ) -> Result<(), LoadError> {
+        let defined: HashMap<String, FunctionReference>
+            = self.define_functions(&mut HashMap::new(), Some (module_name), module, loading)?;
+        for (name, reference) in &defined {
+            self.functions_by_name.insert(format!("{}.{}", module_name, name), *reference);
+        }
+        self.modules.insert(module_name.to_string(), defined);
+        Ok (())
+    }
+
+    /// Finds the file for a module on the search path, where each segment of a qualified module
+    /// name is a directory
+    fn find_module(&self, module_name: &str) -> Result<PathBuf, LoadError> {
+        let relative: PathBuf = module_name.split('.').collect::<PathBuf>()
+            .with_extension(SOURCE_EXTENSION);
+        self.search_path.iter()
+            .map(|directory| directory.join(&relative))
+            .find(|path| path.is_file())
+            .ok_or_else(|| LoadError::ModuleNotFound (module_name.to_string()))
+    }
+
+    /// Imports a module into a scope, loading it if necessary, and records the unqualified names
+    /// it brings into the scope, unless only its qualified names are imported
+    ///
+    /// Names imported from the standard prelude never replace names that are already in scope,
+    /// and can be replaced by names imported from other modules.
+    fn import_into(
+        &mut self,
+        scope: &mut HashMap<String, FunctionReference>,
+        imported: &mut HashSet<String>,
+        module_name: &str,
+        qualified: bool,
+        loading: &mut Vec<String>,
+    ) -> Result<(), LoadError> {
+        if !self.modules.contains_key(module_name) {
+            if loading.iter().any(|name| name == module_name) {
+                return Err (LoadError::CyclicImport (module_name.to_string()));
+            }
+            let module: UnresolvedModule = read_module(&self.find_module(module_name)?)?;
+            if module.name() != Some (module_name) {
+                return Err (LoadError::MismatchedModuleName {
+                    expected: module_name.to_string(),
+                    declared: module.name().map(str::to_string),
+                });
+            }
+            loading.push(module_name.to_string());
+            self.define_module(module_name, &module, loading)?;
+            loading.pop();
+        }
+        let exported: &HashMap<String, FunctionReference> = &self.modules[module_name];
+        let is_prelude: bool = module_name == PRELUDE_MODULE;
+        for (name, reference) in exported {
+            scope.insert(format!("{}.{}", module_name, name), *reference);
+        }
+        if qualified { return Ok (()); }
+        for (name, reference) in exported {
+            if is_reserved_word(name) || scope.get(name).is_some_and(|existing|
+                existing != reference && !is_prelude && !self.is_prelude(name, *existing)
+            ) {
+                return Err (LoadError::Conflict (name.clone()));
+            }
+        }
+        for (name, reference) in exported {
+            if is_prelude {
+                scope.entry(name.clone()).or_insert(*reference);
+            } else {
+                scope.insert(name.clone(), *reference);
+                imported.insert(name.clone());
+            }
+        }
+        Ok (())
+    }
+
+    /// Returns whether a function is the one that the standard prelude defines with a name
+    fn is_prelude(&self, name: &str, reference: FunctionReference) -> bool {
+        self.modules.get(PRELUDE_MODULE)
+            .and_then(|prelude| prelude.get(name))
+            .is_some_and(|prelude_reference| *prelude_reference == reference)
+    }
+
+}
+
+/// Parses the source of a file at a given path
+fn parse_module(path: &Path, source: &str) -> Result<UnresolvedModule, LoadError> {
+    let input: Text = Text::from_string(source);
+    let result: ParseResult<UnresolvedModule> = UnresolvedModule::parse
+        .then_ignore(end())
+        .parse(&input);
+    match result {
+        ParseResult::Success (module, _) => match module.errors().first() {
+            Some (error) => Err (LoadError::Declaration (path.to_path_buf(), error.clone())),
+            None => Ok (module),
+        },
+        ParseResult::Failure (_, _) => Err (LoadError::Syntax (path.to_path_buf())),
+    }
+}
+
+/// Reads and parses a source file
+fn read_module(path: &Path) -> Result<UnresolvedModule, LoadError> {
+    let source: String = read_to_string(path)
+        .map_err(|error| LoadError::Read (path.to_path_buf(), error))?;
+    parse_module(path, &source)
 }
 
 /// Resolves an unresolved function body, and stores it in this `Namespace` at a specified
@@ -113,62 +460,522 @@ fn resolve(
     function_reference: FunctionReference,
     unresolved_body: &[UnresolvedTerm],
 ) -> Result<(), HashSet<String>> {
-    use UnresolvedTerm::*;
     let mut resolved: Vec<Term> = Vec::with_capacity(unresolved_body.len());
     let mut undefined: HashSet<String> = HashSet::new();
+    resolve_terms(
+        environment,
+        functions_by_name,
+        &mut Vec::new(),
+        unresolved_body,
+        &mut resolved,
+        &mut undefined,
+    );
+    if undefined.is_empty() {
+        function_reference.set_body(environment, &resolved);
+        Ok (())
+    } else { Err (undefined) }
+}
+
+/// Resolves unresolved terms into `Term`s, where `locals` holds the names bound to each local slot
+/// of the frame the terms will be evaluated in
+fn resolve_terms(
+    environment: &mut Environment,
+    functions_by_name: &HashMap<String, FunctionReference>,
+    locals: &mut Vec<String>,
+    unresolved_body: &[UnresolvedTerm],
+    resolved: &mut Vec<Term>,
+    undefined: &mut HashSet<String>,
+) {
+    use UnresolvedTerm::*;
     for unresolved_term in unresolved_body {
         match unresolved_term {
             // nothing needs to be done with already resolved terms
             Resolved (term) => resolved.push(term.clone()),
-            // resolve function applications
+            // resolve local names and function applications
             UnresolvedApplication (unresolved_name) =>
-                if let Some (reference) = functions_by_name.get(unresolved_name) {
+                if let Some (slot) = locals.iter().rposition(|name| name == unresolved_name) {
+                    resolved.push(Term::Local (slot));
+                } else if let Some (reference) = functions_by_name.get(unresolved_name) {
                     resolved.push(Term::Application (*reference));
                 } else { undefined.insert(unresolved_name.to_string()); },
-            // resolve lambdas
-            UnresolvedLambda (lambda_body) => {
-                let lambda_reference: FunctionReference
-                    = FunctionReference::reserve(environment);
-                let lambda: Value = Value::Lambda (
-                    match resolve(environment, functions_by_name, lambda_reference, lambda_body) {
-                        Ok (_) => LambdaReference::from_function(lambda_reference),
-                        Err (lambda_undefined) => {
-                            undefined.extend(lambda_undefined);
-                            LambdaReference::from_function(lambda_reference)
-                        }
-                    }
+            // resolve local bindings to frame slots for the scope of their body
+            UnresolvedBinding (names, binding_body) => {
+                let slot: usize = locals.len();
+                resolved.push(Term::Bind (slot, names.iter().cloned().collect()));
+                locals.extend(names.iter().cloned());
+                resolve_terms(
+                    environment,
+                    functions_by_name,
+                    locals,
+                    binding_body,
+                    resolved,
+                    undefined
                 );
-                resolved.push(Term::Data (lambda));
+                locals.truncate(slot);
+                resolved.push(Term::Unbind (slot));
             }
-            // resolve lists
+            // resolve lambdas
+            UnresolvedLambda (lambda_body) => resolved.push(
+                resolve_lambda(environment, functions_by_name, locals, lambda_body, undefined)
+            ),
+            // resolve lists, building them at runtime if they contain closures
             UnresolvedList (list_items) => {
-                let mut items: Vec<Value> = Vec::with_capacity(list_items.len());
-                for item in list_items {
-                    match item {
-                        Resolved (Term::Data (value)) => items.push(value.clone()),
-                        UnresolvedLambda (lambda_body) => {
-                            let lambda_reference: FunctionReference
-                                = FunctionReference::reserve(environment);
-                            let lambda: Value = Value::Lambda (
-                                match resolve(environment, functions_by_name, lambda_reference, lambda_body) {
-                                    Ok (_) => LambdaReference::from_function(lambda_reference),
-                                    Err (lambda_undefined) => {
-                                        undefined.extend(lambda_undefined);
-                                        LambdaReference::from_function(lambda_reference)
-                                    }
-                                }
-                            );
-                            items.push(lambda);
-                        }
-                        _ => unreachable!("No other terms should be parsed by list parsers")
+                let items: Vec<Vec<Term>> = resolve_items(
+                    environment,
+                    functions_by_name,
+                    locals,
+                    list_items,
+                    undefined
+                );
+                if items.iter().all(|item| is_constant(item)) {
+                    resolved.push(Term::Data (Value::List (items.into_iter()
+                        .map(constant_value)
+                        .collect()
+                    )));
+                } else {
+                    resolved.push(Term::Data (Value::List (Vec::with_capacity(items.len()))));
+                    for item in items {
+                        resolved.extend(item);
+                        resolved.push(Term::Combinator (Combinator::Append));
+                    }
+                }
+            }
+            // resolve maps, building them at runtime if they contain closures
+            UnresolvedMap (map_items) => {
+                let items: Vec<Vec<Term>> = resolve_items(
+                    environment,
+                    functions_by_name,
+                    locals,
+                    map_items,
+                    undefined
+                );
+                if items.iter().all(|item| is_constant(item)) {
+                    let mut entries: BTreeMap<Value, Value> = BTreeMap::new();
+                    let mut values = items.into_iter().map(constant_value);
+                    while let (Some (key), Some (value)) = (values.next(), values.next()) {
+                        entries.insert(key, value);
+                    }
+                    resolved.push(Term::Data (Value::Map (entries)));
+                } else {
+                    resolved.push(Term::Data (Value::Map (BTreeMap::new())));
+                    let mut items = items.into_iter();
+                    while let (Some (key), Some (value)) = (items.next(), items.next()) {
+                        resolved.extend(key);
+                        resolved.extend(value);
+                        resolved.push(Term::Combinator (Combinator::Put));
                     }
                 }
-                resolved.push(Term::Data (Value::List (items)));
             }
         }
     }
-    if undefined.is_empty() {
-        function_reference.set_body(environment, &resolved);
-        Ok (())
-    } else { Err (undefined) }
-}
\ No newline at end of file
+}
+
+/// Returns the `Value` pushed by the terms of a constant item of a list or map
+fn constant_value(item: Vec<Term>) -> Value {
+    match item.into_iter().next() {
+        Some (Term::Data (value)) => value,
+        _ => unreachable!("Constant items are a single data term"),
+    }
+}
+
+/// Returns whether the terms of an item of a list or map only push a constant `Value`
+fn is_constant(item: &[Term]) -> bool { matches!(item, [Term::Data (_)]) }
+
+/// Resolves each item of a list or map into the terms that push it
+fn resolve_items(
+    environment: &mut Environment,
+    functions_by_name: &HashMap<String, FunctionReference>,
+    locals: &mut Vec<String>,
+    unresolved_items: &[UnresolvedTerm],
+    undefined: &mut HashSet<String>,
+) -> Vec<Vec<Term>> {
+    unresolved_items.iter()
+        .map(|unresolved_item| {
+            let mut item: Vec<Term> = Vec::with_capacity(1);
+            resolve_terms(
+                environment,
+                functions_by_name,
+                locals,
+                std::slice::from_ref(unresolved_item),
+                &mut item,
+                undefined
+            );
+            item
+        })
+        .collect()
+}
+
+/// Resolves an unresolved lambda body into a `Term` that pushes the lambda, capturing any local
+/// names from the enclosing frame that it uses
+fn resolve_lambda(
+    environment: &mut Environment,
+    functions_by_name: &HashMap<String, FunctionReference>,
+    locals: &[String],
+    unresolved_body: &[UnresolvedTerm],
+    undefined: &mut HashSet<String>,
+) -> Term {
+    let mut captured: BTreeSet<usize> = BTreeSet::new();
+    captured_locals(unresolved_body, locals, &mut Vec::new(), &mut captured);
+    let captured: Vec<usize> = captured.into_iter().collect();
+    let mut lambda_locals: Vec<String> = captured.iter()
+        .map(|slot| locals[*slot].clone())
+        .collect();
+    let mut resolved: Vec<Term> = Vec::with_capacity(unresolved_body.len());
+    let mut lambda_undefined: HashSet<String> = HashSet::new();
+    if !captured.is_empty() {
+        resolved.push(Term::Bind (0, lambda_locals.iter().cloned().collect()));
+    }
+    resolve_terms(
+        environment,
+        functions_by_name,
+        &mut lambda_locals,
+        unresolved_body,
+        &mut resolved,
+        &mut lambda_undefined
+    );
+    if !captured.is_empty() { resolved.push(Term::Unbind (0)); }
+    let lambda_reference: FunctionReference = if lambda_undefined.is_empty() {
+        FunctionReference::intern(environment, &resolved)
+    } else {
+        undefined.extend(lambda_undefined);
+        FunctionReference::reserve(environment)
+    };
+    if captured.is_empty() {
+        Term::Data (Value::Lambda (LambdaReference::from_function(environment, lambda_reference)))
+    } else { Term::Closure (lambda_reference, captured) }
+}
+
+/// Finds the local slots of an enclosing frame that are used by an unresolved body, ignoring
+/// names that are shadowed by bindings within the body
+fn captured_locals(
+    unresolved_body: &[UnresolvedTerm],
+    locals: &[String],
+    shadowed: &mut Vec<String>,
+    captured: &mut BTreeSet<usize>,
+) {
+    use UnresolvedTerm::*;
+    for unresolved_term in unresolved_body {
+        match unresolved_term {
+            Resolved (_) => {}
+            UnresolvedApplication (name) => if !shadowed.contains(name) {
+                if let Some (slot) = locals.iter().rposition(|local| local == name) {
+                    captured.insert(slot);
+                }
+            },
+            UnresolvedBinding (names, binding_body) => {
+                let shadowed_count: usize = shadowed.len();
+                shadowed.extend(names.iter().cloned());
+                captured_locals(binding_body, locals, shadowed, captured);
+                shadowed.truncate(shadowed_count);
+            }
+            UnresolvedLambda (body) | UnresolvedList (body) | UnresolvedMap (body) =>
+                captured_locals(body, locals, shadowed, captured),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::testing::{
+        define,
+        evaluate,
+        evaluate_bare,
+        evaluate_in,
+        source_directory,
+    };
+    use super::*;
+
+    #[test]
+    fn bindings_pop_values_into_named_locals() {
+        assert_eq!(evaluate_bare("1 2 3 [a b c] -> ( a b * c + )"), Ok ("5".to_string()));
+        assert_eq!(evaluate_bare("1 2 [a b] -> ( b a )"), Ok ("2 1".to_string()));
+    }
+
+    #[test]
+    fn bindings_shadow_enclosing_locals() {
+        assert_eq!(
+            evaluate_bare("5 [x] -> ( x [x] -> ( x x * ) x )"),
+            Ok ("25 5".to_string())
+        );
+    }
+
+    #[test]
+    fn bindings_fail_without_enough_values() {
+        assert_eq!(
+            evaluate_bare("1 [a b] -> ( a )"),
+            Err ("Not enough items in the stack to bind local names".to_string())
+        );
+    }
+
+    #[test]
+    fn bindings_work_in_definitions_and_lambdas() {
+        let mut namespace: Namespace = Namespace::new();
+        define(&mut namespace, "hypotenuse_squared : [a b] -> ( a a * b b * + ) ;");
+        assert_eq!(evaluate_in(&mut namespace, "3 4 hypotenuse_squared"), Ok ("25".to_string()));
+        assert_eq!(
+            evaluate_in(&mut namespace, "[ 1 2 ] ( [x] -> ( x x + ) ) map"),
+            Ok ("[ 2 4 ]".to_string())
+        );
+    }
+
+    #[test]
+    fn lambdas_capture_locals_that_outlive_their_frame() {
+        assert_eq!(evaluate_bare("3 [n] -> ( ( n + ) ) 4 swap apply"), Ok ("7".to_string()));
+    }
+
+    #[test]
+    fn closures_are_written_with_their_captured_values_bound_to_names() {
+        assert_eq!(
+            evaluate_bare("3 [n] -> ( ( n + ) )"),
+            Ok ("( 3 [ n ] -> ( n + ) )".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 2 [a b] -> ( ( a [c] -> ( a b c ) ) )"),
+            Ok ("( 1 2 [ a b ] -> ( a [ c ] -> ( a b c ) ) )".to_string())
+        );
+    }
+
+    #[test]
+    fn closure_terms_are_written_as_they_were_in_source() {
+        assert_eq!(
+            evaluate_bare("( [a] -> ( ( a ) ) )"),
+            Ok ("( [ a ] -> ( ( a ) ) )".to_string())
+        );
+    }
+
+    #[test]
+    fn closures_with_equal_captured_values_are_equal() {
+        assert_eq!(
+            evaluate_bare("3 [n] -> ( ( n + ) ) 3 [n] -> ( ( n + ) ) ="),
+            Ok ("true".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("3 [n] -> ( ( n + ) ) 4 [n] -> ( ( n + ) ) ="),
+            Ok ("false".to_string())
+        );
+    }
+
+    #[test]
+    fn curried_values_are_pushed_before_the_body() {
+        assert_eq!(evaluate_bare("5 ( + ) curry"), Ok ("( 5 + )".to_string()));
+        assert_eq!(evaluate_bare("5 ( - ) curry 2 swap apply"), Ok ("-3".to_string()));
+        assert_eq!(evaluate_bare("5 ( + ) curry 5 ( + ) curry ="), Ok ("true".to_string()));
+    }
+
+    #[test]
+    fn prelude_nip_drops_the_second_value() {
+        assert_eq!(evaluate("1 2 nip"), Ok ("2".to_string()));
+        assert_eq!(evaluate("1 2 3 nip"), Ok ("1 3".to_string()));
+    }
+
+    #[test]
+    fn prelude_over_copies_the_second_value() {
+        assert_eq!(evaluate("1 2 over"), Ok ("1 2 1".to_string()));
+    }
+
+    #[test]
+    fn prelude_tuck_copies_the_top_value_below_the_second() {
+        assert_eq!(evaluate("1 2 tuck"), Ok ("2 1 2".to_string()));
+    }
+
+    #[test]
+    fn prelude_dup2_copies_the_top_two_values() {
+        assert_eq!(evaluate("1 2 dup2"), Ok ("1 2 1 2".to_string()));
+    }
+
+    #[test]
+    fn prelude_square_multiplies_a_number_by_itself() {
+        assert_eq!(evaluate("-3 square"), Ok ("9".to_string()));
+        assert_eq!(evaluate("2/3 square"), Ok ("4/9".to_string()));
+        assert_eq!(evaluate("1.5 square"), Ok ("9/4".to_string()));
+    }
+
+    #[test]
+    fn prelude_even_and_odd_test_parity() {
+        assert_eq!(evaluate("0 even 0 odd"), Ok ("true false".to_string()));
+        assert_eq!(evaluate("4 even 7 even"), Ok ("true false".to_string()));
+        assert_eq!(evaluate("-3 odd -4 odd"), Ok ("true false".to_string()));
+    }
+
+    #[test]
+    fn prelude_functions_fail_without_enough_values() {
+        assert!(evaluate("1 nip").is_err());
+        assert!(evaluate("1 over").is_err());
+        assert!(evaluate("square").is_err());
+    }
+
+    #[test]
+    fn integers_are_written_in_the_chosen_radix() {
+        let mut namespace: Namespace = Namespace::new();
+        namespace.set_integer_radix(16);
+        assert_eq!(
+            evaluate_in(&mut namespace, "255 -16 [ 10 ]"),
+            Ok ("0xff -0x10 [ 0xa ]".to_string())
+        );
+        namespace.set_integer_radix(2);
+        assert_eq!(evaluate_in(&mut namespace, "5"), Ok ("0b101".to_string()));
+        namespace.set_integer_radix(10);
+        assert_eq!(evaluate_in(&mut namespace, "255"), Ok ("255".to_string()));
+    }
+
+    #[test]
+    fn modules_are_registered_when_they_are_loaded() {
+        let mut namespace: Namespace = Namespace::new();
+        namespace.add_search_path(source_directory(&[
+            ("math.cmp", "module math; plus : + ;"),
+            ("other.cmp", "module other; plus : - ;"),
+        ]));
+        namespace.import("math").unwrap();
+        assert!(matches!(
+            namespace.import("other"),
+            Err (LoadError::Conflict (name)) if name == "plus"
+        ));
+        assert_eq!(evaluate_in(&mut namespace, "3 1 plus"), Ok ("4".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "3 1 other.plus"), Ok ("2".to_string()));
+    }
+
+    #[test]
+    fn qualified_imports_only_bring_qualified_names_into_scope() {
+        let mut namespace: Namespace = Namespace::new();
+        namespace.add_search_path(source_directory(&[
+            ("math.cmp", "module math; plus : + ;"),
+            ("stats.cmp", "module stats; import qualified math; total: 0 swap ( math.plus ) fold;"),
+        ]));
+        namespace.import_qualified("stats").unwrap();
+        assert_eq!(evaluate_in(&mut namespace, "[ 1 2 3 ] stats.total"), Ok ("6".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "1 2 math.plus"), Ok ("3".to_string()));
+        let input: Text = Text::from_string("main : 1 2 plus ;");
+        let ParseResult::Success (function, _) = UnresolvedFunction::parse.parse(&input)
+        else { unreachable!() };
+        assert!(matches!(namespace.define(&function), Err (LoadError::Undefined (_))));
+    }
+
+    #[test]
+    fn definitions_named_by_reserved_words_are_rejected() {
+        let mut namespace: Namespace = Namespace::new();
+        let reserved: [&str; 11] = [
+            "sum", "product", "range", "reverse", "min", "max", "abs", "negate", "times", "while",
+            "true",
+        ];
+        for name in reserved {
+            let source: String = format!("{} : 42 ;", name);
+            let input: Text = Text::from_string(&source);
+            let ParseResult::Success (function, _) = UnresolvedFunction::parse.parse(&input)
+            else { unreachable!() };
+            assert!(matches!(
+                namespace.define(&function),
+                Err (LoadError::Conflict (conflict)) if conflict == name
+            ));
+        }
+        define(&mut namespace, "summary : 42 ;");
+        assert_eq!(evaluate_in(&mut namespace, "summary"), Ok ("42".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "[ 1 2 ] sum"), Ok ("3".to_string()));
+    }
+
+    #[test]
+    fn modules_defining_reserved_words_cannot_be_imported_or_loaded() {
+        let mut namespace: Namespace = Namespace::new();
+        let directory: PathBuf = source_directory(&[
+            ("stats.cmp", "module stats; total : 0 swap ( + ) fold ; sum : total ;"),
+            ("loose.cmp", "range : 0 ;"),
+        ]);
+        namespace.add_search_path(directory.clone());
+        assert!(matches!(
+            namespace.import("stats"),
+            Err (LoadError::Conflict (name)) if name == "stats.sum"
+        ));
+        assert!(matches!(
+            namespace.load(&directory.join("loose.cmp")),
+            Err (LoadError::Conflict (name)) if name == "range"
+        ));
+        assert_eq!(evaluate_in(&mut namespace, "[ 1 2 ] reverse"), Ok ("[ 2 1 ]".to_string()));
+    }
+
+    #[test]
+    fn imports_shadow_prelude_names() {
+        let mut namespace: Namespace = Namespace::with_prelude();
+        namespace.add_search_path(source_directory(&[
+            ("cube.cmp", "module cube; square : copy copy * * ;"),
+            ("area.cmp", "module area; import prelude; square : copy * 2 / ; half : 1 square ;"),
+        ]));
+        namespace.import("cube").unwrap();
+        assert_eq!(evaluate_in(&mut namespace, "2 square"), Ok ("8".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "2 prelude.square"), Ok ("4".to_string()));
+        namespace.import_qualified("area").unwrap();
+        assert_eq!(evaluate_in(&mut namespace, "4 area.square"), Ok ("8".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "2 square"), Ok ("8".to_string()));
+    }
+
+    #[test]
+    fn the_prelude_does_not_shadow_imported_names() {
+        let mut namespace: Namespace = Namespace::with_prelude();
+        namespace.add_search_path(source_directory(&[
+            ("cube.cmp", "module cube; square : copy copy * * ;"),
+            ("volume.cmp", "module volume; import cube; import prelude; twice : 2 square ;"),
+        ]));
+        namespace.import("volume").unwrap();
+        assert_eq!(evaluate_in(&mut namespace, "twice"), Ok ("8".to_string()));
+    }
+
+    #[test]
+    fn loading_a_file_defines_its_functions_in_the_root_scope() {
+        let mut namespace: Namespace = Namespace::with_prelude();
+        let directory: PathBuf = source_directory(&[
+            ("first.cmp", "square : copy copy * * ; cube : square ;"),
+        ]);
+        namespace.load(&directory.join("first.cmp")).unwrap();
+        assert_eq!(evaluate_in(&mut namespace, "2 cube"), Ok ("8".to_string()));
+        assert_eq!(evaluate_in(&mut namespace, "2 prelude.square"), Ok ("4".to_string()));
+    }
+
+    #[test]
+    fn loading_a_file_does_not_replace_existing_definitions() {
+        let mut namespace: Namespace = Namespace::with_prelude();
+        let directory: PathBuf = source_directory(&[
+            ("first.cmp", "cube : copy copy * * ;"),
+            ("second.cmp", "cube : 0 ;"),
+            ("math.cmp", "module math; plus : + ;"),
+        ]);
+        namespace.load(&directory.join("first.cmp")).unwrap();
+        assert!(matches!(
+            namespace.load(&directory.join("second.cmp")),
+            Err (LoadError::Conflict (name)) if name == "cube"
+        ));
+        assert_eq!(evaluate_in(&mut namespace, "2 cube"), Ok ("8".to_string()));
+        namespace.load(&directory.join("math.cmp")).unwrap();
+        assert!(matches!(
+            namespace.load(&directory.join("math.cmp")),
+            Err (LoadError::Conflict (name)) if name == "math"
+        ));
+    }
+
+    #[test]
+    fn imports_of_unknown_or_cyclic_modules_fail() {
+        let mut namespace: Namespace = Namespace::new();
+        namespace.add_search_path(source_directory(&[
+            ("ping.cmp", "module ping; import pong; ping : pong ;"),
+            ("pong.cmp", "module pong; import ping; pong : ping ;"),
+        ]));
+        assert!(matches!(namespace.import("missing"), Err (LoadError::ModuleNotFound (_))));
+        assert!(matches!(
+            namespace.import("ping"),
+            Err (LoadError::CyclicImport (name)) if name == "ping"
+        ));
+    }
+
+    #[test]
+    fn loading_a_file_with_repeated_field_names_fails() {
+        let mut namespace: Namespace = Namespace::new();
+        let directory: PathBuf = source_directory(&[
+            ("point.cmp", "record point x x ;"),
+            ("shape.cmp", "union shape circle(r) square(s) circle ;"),
+        ]);
+        let error: LoadError = namespace.load(&directory.join("point.cmp")).unwrap_err();
+        assert!(error.to_string().starts_with("`x` is declared more than once in `point` in"));
+        let error: LoadError = namespace.load(&directory.join("shape.cmp")).unwrap_err();
+        assert!(error.to_string().starts_with("`circle` is declared more than once in `shape` in"));
+    }
+
+}
@@ -0,0 +1,79 @@
+// Copyright Rob Gage 2025
+
+use crate::DeclarationError;
+use std::{
+    collections::HashSet,
+    fmt::{
+        Display,
+        Formatter,
+        self,
+    },
+    io,
+    path::PathBuf,
+};
+
+/// An error encountered while loading source files or modules into a `Namespace`
+#[derive(Debug)]
+pub enum LoadError {
+
+    /// A name was defined or imported more than once in the same scope, or was defined although it
+    /// is a reserved word such as the name of a combinator
+    Conflict (String),
+
+    /// A module imported itself, directly or through other modules
+    CyclicImport (String),
+
+    /// A source file contained an invalid record or union declaration
+    Declaration (PathBuf, DeclarationError),
+
+    /// A module file declared a different module name than the one it was imported as
+    MismatchedModuleName {
+        /// The name the module was imported as
+        expected: String,
+        /// The name the module file declared
+        declared: Option<String>,
+    },
+
+    /// A module could not be found on the search path
+    ModuleNotFound (String),
+
+    /// A source file could not be read
+    Read (PathBuf, io::Error),
+
+    /// A source file contained invalid syntax
+    Syntax (PathBuf),
+
+    /// Functions used by the source were not defined
+    Undefined (HashSet<String>),
+
+}
+
+impl Display for LoadError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict (name) => write!(f, "`{}` is defined more than once in the same scope, \
+                or is a reserved word", name),
+            Self::CyclicImport (name) => write!(f, "Module `{}` imports itself", name),
+            Self::Declaration (path, error) => write!(f, "{} in `{}`", error, path.display()),
+            Self::MismatchedModuleName { expected, declared: Some (declared) } => write!(f,
+                "Expected module `{}`, but the module file declares `{}`", expected, declared),
+            Self::MismatchedModuleName { expected, declared: None } => write!(f,
+                "Expected module `{}`, but the module file does not declare a module", expected),
+            Self::ModuleNotFound (name) => write!(f, "Module `{}` was not found on the search path",
+                name),
+            Self::Read (path, error) => write!(f, "Could not read `{}`: {}", path.display(), error),
+            Self::Syntax (path) => write!(f, "Invalid syntax in `{}`", path.display()),
+            Self::Undefined (names) => {
+                let mut names: Vec<&String> = names.iter().collect();
+                names.sort();
+                write!(f, "Missing required functions:")?;
+                for name in names {
+                    write!(f, " `{}`", name)?;
+                }
+                Ok (())
+            }
+        }
+    }
+
+}
@@ -0,0 +1,195 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Float,
+    Integer,
+};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{
+    Pow,
+    Signed,
+    ToPrimitive,
+    Zero,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        self,
+    },
+    ops::{
+        Add,
+        Div,
+        Mul,
+        Neg,
+        Rem,
+        Sub
+    },
+    str::FromStr
+};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Rational (BigRational);
+
+impl Rational {
+
+    /// Creates a `Rational` from a string slice of decimal digits with an optional sign and
+    /// fractional part, such as `-1.25`
+    pub fn from_decimal(string: &str) -> Option<Self> {
+        let (whole, fraction): (&str, &str) = string.split_once('.').unwrap_or((string, ""));
+        let numerator: BigInt = BigInt::from_str(&format!("{}{}", whole, fraction)).ok()?;
+        let denominator: BigInt = BigInt::from(10).pow(fraction.len());
+        Some (Self (BigRational::new(numerator, denominator)))
+    }
+
+    /// Creates a `Rational` from a numerator and a denominator, returning `None` if the
+    /// denominator is zero
+    pub fn from_fraction(numerator: Integer, denominator: Integer) -> Option<Self> {
+        if denominator.0.is_zero() { return None; }
+        Some (Self (BigRational::new(numerator.0, denominator.0)))
+    }
+
+    /// Creates a `Rational` with the same value as an `Integer`
+    pub fn from_integer(integer: Integer) -> Self {
+        Self (BigRational::from_integer(integer.0))
+    }
+
+    /// Returns the absolute value of this `Rational`
+    pub fn abs(&self) -> Self { Self (self.0.abs()) }
+
+    /// Returns the least `Integer` that is greater than or equal to this `Rational`
+    pub fn ceil(&self) -> Integer { Integer (self.0.ceil().to_integer()) }
+
+    /// Returns the denominator of this `Rational` in lowest terms, which is always positive
+    pub fn denominator(&self) -> Integer { Integer (self.0.denom().clone()) }
+
+    /// Returns the greatest `Integer` that is less than or equal to this `Rational`
+    pub fn floor(&self) -> Integer { Integer (self.0.floor().to_integer()) }
+
+    /// Returns whether this `Rational` is zero
+    pub fn is_zero(&self) -> bool { self.0.is_zero() }
+
+    /// Returns the numerator of this `Rational` in lowest terms
+    pub fn numerator(&self) -> Integer { Integer (self.0.numer().clone()) }
+
+    /// Raises this `Rational` to an integer power, returning `None` if it is raised to a negative
+    /// power while zero, or if the numerator or denominator of the result would be too large
+    pub fn pow(&self, exponent: &Integer) -> Option<Self> {
+        let magnitude: Integer = exponent.abs();
+        let numerator: Integer = self.numerator().pow(&magnitude)?;
+        let denominator: Integer = self.denominator().pow(&magnitude)?;
+        if exponent.is_negative() {
+            Self::from_fraction(denominator, numerator)
+        } else { Self::from_fraction(numerator, denominator) }
+    }
+
+    /// Returns the nearest `Integer` to this `Rational`, rounding half-way cases away from zero
+    pub fn round(&self) -> Integer { Integer (self.0.round().to_integer()) }
+
+    /// Returns the nearest `Float` to this `Rational`
+    pub fn to_float(&self) -> Float {
+        Float::new(self.0.to_f64().unwrap_or(f64::NAN))
+    }
+
+    /// Returns -1, 0 or 1 depending on whether this `Rational` is negative, zero or positive
+    pub fn signum(&self) -> Integer { Integer (self.0.signum().to_integer()) }
+
+    /// Returns this `Rational` as an `Integer` if it is a whole number
+    pub fn to_integer(&self) -> Option<Integer> {
+        if self.0.is_integer() { Some (Integer (self.0.to_integer())) } else { None }
+    }
+
+}
+
+impl Display for Rational {
+
+    /// Writes the `Rational` as a fraction in lowest terms
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.0.numer(), self.0.denom())
+    }
+
+}
+
+impl Add for Rational {
+    type Output = Self;
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self (self.0 + other.0)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+    #[inline]
+    fn div(self, other: Self) -> Self {
+        Self (self.0 / other.0)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        Self (self.0 * other.0)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self (-self.0)
+    }
+}
+
+impl Rem for Rational {
+    type Output = Self;
+    #[inline]
+    fn rem(self, other: Self) -> Self {
+        Self (self.0 % other.0)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self (self.0 - other.0)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn decimals_are_parsed_exactly() {
+        assert_eq!(Rational::from_decimal("1.25").unwrap().to_string(), "5/4");
+        assert_eq!(Rational::from_decimal("-0.5").unwrap().to_string(), "-1/2");
+        assert_eq!(Rational::from_decimal("0.1").unwrap().to_string(), "1/10");
+    }
+
+    #[test]
+    fn fractions_need_a_nonzero_denominator() {
+        let fraction = |numerator: isize, denominator: isize| Rational::from_fraction(
+            Integer::from_isize(numerator),
+            Integer::from_isize(denominator)
+        );
+        assert_eq!(fraction(6, 4).unwrap().to_string(), "3/2");
+        assert!(fraction(1, 0).is_none());
+    }
+
+    #[test]
+    fn rounding_rounds_halves_away_from_zero() {
+        let rational: Rational = Rational::from_decimal("-3.5").unwrap();
+        assert_eq!(rational.floor(), Integer::from_isize(-4));
+        assert_eq!(rational.ceil(), Integer::from_isize(-3));
+        assert_eq!(rational.round(), Integer::from_isize(-4));
+        assert_eq!(Rational::from_decimal("2.5").unwrap().round(), Integer::from_isize(3));
+    }
+
+}
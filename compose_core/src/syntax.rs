@@ -1,11 +1,26 @@
 // Copyright Rob Gage 2025
 
+mod declaration_error;
 mod syntax_error;
 mod unresolved_function;
+mod unresolved_import;
+mod unresolved_module;
+mod unresolved_record;
 mod unresolved_term;
+mod unresolved_union;
 
+use declaration_error::first_duplicate;
 use syntax_error::SyntaxErrorVariant;
 
+pub use declaration_error::DeclarationError;
 pub use syntax_error::SyntaxError;
 pub use unresolved_function::UnresolvedFunction;
-pub use unresolved_term::UnresolvedTerm;
\ No newline at end of file
+pub use unresolved_import::UnresolvedImport;
+pub use unresolved_module::UnresolvedModule;
+pub use unresolved_record::UnresolvedRecord;
+pub use unresolved_union::UnresolvedUnion;
+pub use unresolved_term::{
+    is_reserved_word,
+    literal_errors,
+    UnresolvedTerm,
+};
\ No newline at end of file
@@ -0,0 +1,106 @@
+// Copyright Rob Gage 2025
+
+use std::fmt::{
+    Display,
+    Formatter,
+    self,
+};
+
+/// An error in a record or union declaration that parsed successfully, such as a field name that
+/// is declared more than once
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeclarationError {
+    /// The name of the record, union or union variant that the error is in
+    declaration: String,
+    /// The name that is declared more than once
+    name: String,
+}
+
+impl DeclarationError {
+
+    /// Creates a new `DeclarationError` for a name declared more than once in a declaration
+    pub fn duplicate(declaration: &str, name: &str) -> Self {
+        Self { declaration: declaration.to_string(), name: name.to_string() }
+    }
+
+}
+
+impl Display for DeclarationError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is declared more than once in `{}`", self.name, self.declaration)
+    }
+
+}
+
+
+
+/// Returns the first name that is repeated, if any is
+pub fn first_duplicate<'a>(names: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+    let names: Vec<&String> = names.collect();
+    names.iter().enumerate()
+        .find(|(index, name)| names[..*index].contains(name))
+        .map(|(_, name)| *name)
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        UnresolvedRecord,
+        UnresolvedUnion,
+    };
+    use pups::*;
+
+    fn record(source: &str) -> UnresolvedRecord {
+        let input: Text = Text::from_string(source);
+        let result: ParseResult<UnresolvedRecord> = UnresolvedRecord::parse
+            .then_ignore(end())
+            .parse(&input);
+        match result {
+            ParseResult::Success (record, _) => record,
+            ParseResult::Failure (_, _) => panic!("`{}` is not a record declaration", source),
+        }
+    }
+
+    fn union(source: &str) -> UnresolvedUnion {
+        let input: Text = Text::from_string(source);
+        let result: ParseResult<UnresolvedUnion> = UnresolvedUnion::parse
+            .then_ignore(end())
+            .parse(&input);
+        match result {
+            ParseResult::Success (union, _) => union,
+            ParseResult::Failure (_, _) => panic!("`{}` is not a union declaration", source),
+        }
+    }
+
+    #[test]
+    fn records_with_repeated_fields_are_reported() {
+        assert!(record("record point x y ;").validate().is_ok());
+        assert!(record("record unit ;").validate().is_ok());
+        assert_eq!(
+            record("record p x x ;").validate().unwrap_err().to_string(),
+            "`x` is declared more than once in `p`"
+        );
+        assert_eq!(
+            record("record p x y z y x ;").validate().unwrap_err().to_string(),
+            "`y` is declared more than once in `p`"
+        );
+    }
+
+    #[test]
+    fn unions_with_repeated_variants_or_fields_are_reported() {
+        assert!(union("union option none some(x) ;").validate().is_ok());
+        assert!(union("union pair left(x) right(x) ;").validate().is_ok());
+        assert_eq!(
+            union("union option none none ;").validate().unwrap_err().to_string(),
+            "`none` is declared more than once in `option`"
+        );
+        assert_eq!(
+            union("union option none some(x x) ;").validate().unwrap_err().to_string(),
+            "`x` is declared more than once in `some`"
+        );
+    }
+
+}
@@ -25,6 +25,9 @@ impl UnresolvedFunction {
     /// Returns the name of this `UnresolvedFunction`
     pub fn name(&self) -> &str { &self.name }
 
+    /// Creates a new `UnresolvedFunction` from its name and body
+    pub fn new(name: String, body: Vec<UnresolvedTerm>) -> Self { Self { body, name } }
+
     /// Parses an `UnresolvedFunction` from text
     pub fn parse(input: &Text) -> ParseResult<Self> {
         terminated(unicode_identifier(), whitespace().or_not())
@@ -0,0 +1,43 @@
+// Copyright Rob Gage 2025
+
+use super::unresolved_module::qualified_name;
+use pups::*;
+
+/// An import declaration such as `import math` or `import qualified math`, where a qualified import
+/// only brings the qualified names of the module's functions into scope
+#[derive(Clone, Debug)]
+pub struct UnresolvedImport {
+    /// The name of the imported module
+    module_name: String,
+    /// Whether only the qualified names of the module's functions are brought into scope
+    qualified: bool,
+}
+
+impl UnresolvedImport {
+
+    /// Returns whether this `UnresolvedImport` only brings qualified names into scope
+    pub fn is_qualified(&self) -> bool { self.qualified }
+
+    /// Returns the name of the module imported by this `UnresolvedImport`
+    pub fn module_name(&self) -> &str { &self.module_name }
+
+    /// Parses an `UnresolvedImport` from text, optionally terminated by a semicolon
+    pub fn parse(input: &Text) -> ParseResult<Self> {
+        preceded(
+            token("import").then(whitespace()),
+            terminated(
+                choice([
+                    |input| preceded(token("qualified").then(whitespace()), qualified_name)
+                        .map(|module_name| Self { module_name, qualified: true })
+                        .parse(input),
+                    |input| qualified_name
+                        .map(|module_name| Self { module_name, qualified: false })
+                        .parse(input),
+                ]),
+                whitespace().or_not().then(token(";")).or_not()
+            )
+        )
+            .parse(input)
+    }
+
+}
@@ -0,0 +1,126 @@
+// Copyright Rob Gage 2025
+
+use super::{
+    DeclarationError,
+    UnresolvedFunction,
+    UnresolvedImport,
+    UnresolvedRecord,
+    UnresolvedUnion,
+};
+use pups::*;
+
+/// An unresolved source file, made up of an optional module declaration, the modules it imports,
+/// and the functions it defines, including those defined by its record and union declarations
+#[derive(Clone, Debug)]
+pub struct UnresolvedModule {
+    /// The errors in the record and union declarations of the module
+    errors: Vec<DeclarationError>,
+    /// The `UnresolvedFunction`s defined in the module
+    functions: Vec<UnresolvedFunction>,
+    /// The imports declared by the module
+    imports: Vec<UnresolvedImport>,
+    /// The name declared by the module, if it declares one
+    name: Option<String>,
+}
+
+impl UnresolvedModule {
+
+    /// Returns the errors in the record and union declarations of this `UnresolvedModule`
+    pub fn errors(&self) -> &[DeclarationError] { &self.errors }
+
+    /// Returns the `UnresolvedFunction`s defined in this `UnresolvedModule`
+    pub fn functions(&self) -> &[UnresolvedFunction] { &self.functions }
+
+    /// Returns the imports declared by this `UnresolvedModule`
+    pub fn imports(&self) -> &[UnresolvedImport] { &self.imports }
+
+    /// Returns the name declared by this `UnresolvedModule`, if it declares one
+    pub fn name(&self) -> Option<&str> { self.name.as_deref() }
+
+    /// Parses an `UnresolvedModule` from the text of a source file
+    pub fn parse(input: &Text) -> ParseResult<Self> {
+        delimited(
+            whitespace().or_not(),
+            terminated(declaration("module"), whitespace().or_not()).or_not()
+                .then(separated(item, whitespace())),
+            whitespace().or_not(),
+        )
+            .map(|(name, items)| {
+                let mut module: Self =
+                    Self { errors: vec![], functions: vec![], imports: vec![], name };
+                for item in items {
+                    match item {
+                        Item::Function (function) => module.functions.push(function),
+                        Item::Import (import) => module.imports.push(import),
+                        Item::Record (record) => match record.validate() {
+                            Ok (_) => module.functions.extend(record.functions()),
+                            Err (error) => module.errors.push(error),
+                        },
+                        Item::Union (union) => match union.validate() {
+                            Ok (_) => module.functions.extend(union.functions()),
+                            Err (error) => module.errors.push(error),
+                        },
+                    }
+                }
+                module
+            })
+            .parse(input)
+    }
+
+}
+
+
+
+/// An item in a source file
+enum Item {
+    /// A function definition
+    Function (UnresolvedFunction),
+    /// An import declaration
+    Import (UnresolvedImport),
+    /// A record declaration
+    Record (UnresolvedRecord),
+    /// A union declaration
+    Union (UnresolvedUnion),
+}
+
+
+
+/// Parses an item in a source file
+fn item(input: &Text) -> ParseResult<Item> {
+    choice([
+        |input| UnresolvedImport::parse.map(Item::Import).parse(input),
+        |input| UnresolvedRecord::parse.map(Item::Record).parse(input),
+        |input| UnresolvedUnion::parse.map(Item::Union).parse(input),
+        |input| UnresolvedFunction::parse.map(Item::Function).parse(input),
+    ])
+        .parse(input)
+}
+
+
+/// Parses a declaration made of a keyword followed by a module name, and optionally terminated by
+/// a semicolon
+fn declaration<'a>(keyword: &'static str) -> impl Parser<'a, String, (), (), Text> {
+    preceded(
+        token(keyword).then(whitespace()),
+        terminated(
+            qualified_name,
+            whitespace().or_not().then(token(";")).or_not()
+        )
+    )
+}
+
+
+/// Parses a name that may be qualified by the modules it is in, such as `math.sum`
+pub fn qualified_name(input: &Text) -> ParseResult<String> {
+    unicode_identifier()
+        .then(many(preceded(token("."), unicode_identifier())))
+        .map(|(head, tail): (&str, Vec<&str>)| {
+            let mut name: String = head.to_string();
+            for segment in tail {
+                name.push('.');
+                name.push_str(segment);
+            }
+            name
+        })
+        .parse(input)
+}
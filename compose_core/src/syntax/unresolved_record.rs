@@ -0,0 +1,68 @@
+// Copyright Rob Gage 2025
+
+use super::{
+    DeclarationError,
+    first_duplicate,
+    UnresolvedFunction,
+    UnresolvedTerm,
+};
+use crate::{
+    RecordOperation,
+    RecordType,
+    Term,
+};
+use pups::*;
+
+/// A record declaration such as `record point x y ;`, which defines functions to construct records
+/// of a type, access and update their fields, and test whether values are records of the type
+#[derive(Clone, Debug)]
+pub struct UnresolvedRecord {
+    /// The names of the fields of the record type, in order
+    fields: Vec<String>,
+    /// The name of the record type
+    name: String,
+}
+
+impl UnresolvedRecord {
+
+    /// Returns the `UnresolvedFunction`s defined by this `UnresolvedRecord`
+    pub fn functions(&self) -> Vec<UnresolvedFunction> {
+        RecordOperation::declared(RecordType::new(self.name.clone(), self.fields.clone()))
+            .into_iter()
+            .map(|operation| UnresolvedFunction::new(
+                operation.name(),
+                vec![UnresolvedTerm::Resolved (Term::Record (operation))],
+            ))
+            .collect()
+    }
+
+    /// Returns the name of the record type declared by this `UnresolvedRecord`
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns a `DeclarationError` if this `UnresolvedRecord` repeats a field name
+    pub fn validate(&self) -> Result<(), DeclarationError> {
+        match first_duplicate(self.fields.iter()) {
+            Some (field) => Err (DeclarationError::duplicate(&self.name, field)),
+            None => Ok (()),
+        }
+    }
+
+    /// Parses an `UnresolvedRecord` from text, including one that repeats a field name, which
+    /// `UnresolvedRecord::validate` reports
+    pub fn parse(input: &Text) -> ParseResult<Self> {
+        match preceded(
+            token("record").then(whitespace()),
+            unicode_identifier()
+                .then(many(preceded(whitespace(), unicode_identifier())))
+                .then_ignore(whitespace().or_not().then(token(";")))
+        )
+            .parse(input) {
+            ModeResult::Success ((name, fields), messages) => {
+                let fields: Vec<String> = fields.into_iter().map(str::to_string).collect();
+                ModeResult::Success (Self { fields, name: name.to_string() }, messages)
+            }
+            _ => ModeResult::Failure ((), vec![]),
+        }
+    }
+
+}
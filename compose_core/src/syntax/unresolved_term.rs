@@ -1,9 +1,12 @@
 // Copyright Rob Gage 2025
 
+use super::unresolved_module::qualified_name;
 use crate::{
     Combinator,
+    Float,
     Value,
     Integer,
+    Rational,
     Term
 };
 use pups::*;
@@ -17,12 +20,18 @@ pub enum UnresolvedTerm {
     /// Application of an unresolved named function
     UnresolvedApplication (String),
 
+    /// Binds values on the stack to local names for the scope of an unresolved body
+    UnresolvedBinding (Vec<String>, Vec<UnresolvedTerm>),
+
     /// An unresolved lambda term
     UnresolvedLambda (Vec<UnresolvedTerm>),
 
     /// An unresolved list term
     UnresolvedList (Vec<UnresolvedTerm>),
 
+    /// An unresolved map term, made of alternating keys and values
+    UnresolvedMap (Vec<UnresolvedTerm>),
+
 }
 
 impl UnresolvedTerm {
@@ -31,8 +40,13 @@ impl UnresolvedTerm {
     pub fn parse(input: &Text) -> ParseResult<Self> {
         choice([
             lambda,
+            binding,
             list,
+            map,
             boolean,
+            bytes,
+            float,
+            rational,
             integer,
             combinator,
             application,
@@ -56,8 +70,31 @@ impl UnresolvedTerm {
 
 /// Parses a function application term
 fn application(input: &Text) -> ParseResult<UnresolvedTerm> {
-    unicode_identifier()
-        .map(|identifier: &str| UnresolvedTerm::UnresolvedApplication (identifier.to_string()))
+    qualified_name
+        .map(UnresolvedTerm::UnresolvedApplication)
+        .parse(input)
+}
+
+
+/// Parses a local binding term
+fn binding(input: &Text) -> ParseResult<UnresolvedTerm> {
+    delimited(
+        token("[").then(whitespace().or_not()),
+        separated(unicode_identifier(), whitespace()),
+        whitespace().or_not().then(token("]"))
+    )
+        .then(preceded(
+            whitespace().or_not().then(token("->")).then(whitespace().or_not()),
+            delimited(
+                token("(").then(whitespace().or_not()),
+                UnresolvedTerm::parse_many,
+                whitespace().or_not().then(token(")"))
+            )
+        ))
+        .map(|(names, body)| UnresolvedTerm::UnresolvedBinding (
+            names.into_iter().map(|name: &str| name.to_string()).collect(),
+            body
+        ))
         .parse(input)
 }
 
@@ -72,13 +109,60 @@ fn boolean(input: &Text) -> ParseResult<UnresolvedTerm> {
 }
 
 
+/// Parses a bytes term, written as hexadecimal pairs of digits such as `#[ 01 02 ff ]`
+fn bytes(input: &Text) -> ParseResult<UnresolvedTerm> {
+    fn hexadecimal_pair(input: &Text) -> ParseResult<String> {
+        choice((number(), unicode_identifier()))
+            .then(many(choice((number(), unicode_identifier()))))
+            .map(|(head, tail): (&str, Vec<&str>)| {
+                let mut pair: String = head.to_string();
+                for segment in tail {
+                    pair.push_str(segment);
+                }
+                pair
+            })
+            .parse(input)
+    }
+    match delimited(
+        token("#[").then(whitespace().or_not()),
+        separated(hexadecimal_pair, whitespace()),
+        whitespace().or_not().then(token("]"))
+    )
+        .parse(input) {
+        ModeResult::Success (pairs, _) => {
+            let mut bytes: Vec<u8> = Vec::with_capacity(pairs.len());
+            for pair in pairs {
+                match u8::from_str_radix(&pair, 16) {
+                    Ok (byte) if pair.len() == 2 => bytes.push(byte),
+                    _ => return ModeResult::Failure ((), vec![]),
+                }
+            }
+            ModeResult::Success (UnresolvedTerm::Resolved (Term::Data (Value::Bytes (bytes))), vec![])
+        }
+        _ => ModeResult::Failure ((), vec![])
+    }
+}
+
+
 /// Parses a combinator term
 fn combinator(input: &Text) -> ParseResult<UnresolvedTerm> {
     use Combinator::*;
     fn combinator_parser<'a>(
         combinator: Combinator
     ) -> impl Parser<'a, UnresolvedTerm, (), (), Text> {
-        token(combinator.name()).emit(UnresolvedTerm::Resolved (Term::Combinator (combinator)))
+        let term: UnresolvedTerm = UnresolvedTerm::Resolved (Term::Combinator (combinator));
+        move |input: &'a Text| if combinator.name().starts_with(char::is_alphabetic) {
+            // combinators named by words must match a whole word, so that they are not confused
+            // with identifiers that start with the same letters
+            preceded(nothing(), |input: &'a Text| match word.parse(input) {
+                ModeResult::Success (word, messages) if word == combinator.name() =>
+                    ModeResult::Success (term.clone(), messages),
+                _ => ModeResult::Failure ((), vec![]),
+            })
+                .parse(input)
+        } else {
+            token(combinator.name()).emit(term.clone()).parse(input)
+        }
     }
     choice([
         // arithmetic
@@ -87,29 +171,145 @@ fn combinator(input: &Text) -> ParseResult<UnresolvedTerm> {
         combinator_parser(Remainder),
         combinator_parser(Multiply),
         combinator_parser(Subtract),
+        // floating-point
+        combinator_parser(ArcCosine),
+        combinator_parser(ArcSine),
+        combinator_parser(ArcTangent),
+        combinator_parser(Cosine),
+        combinator_parser(Exponential),
+        combinator_parser(FloatToInteger),
+        combinator_parser(IntegerToFloat),
+        combinator_parser(Logarithm),
+        combinator_parser(Power),
+        combinator_parser(Sine),
+        combinator_parser(SquareRoot),
+        combinator_parser(Tangent),
+        // integers
+        combinator_parser(Absolute),
+        combinator_parser(BitAnd),
+        combinator_parser(BitLength),
+        combinator_parser(BitNot),
+        combinator_parser(BitOr),
+        combinator_parser(BitXor),
+        combinator_parser(DivideFloored),
+        combinator_parser(DivideModulo),
+        combinator_parser(GreatestCommonDivisor),
+        combinator_parser(IntegerSquareRoot),
+        combinator_parser(LeastCommonMultiple),
+        combinator_parser(Modulo),
+        combinator_parser(Negate),
+        combinator_parser(PowerModulo),
+        combinator_parser(Quotient),
+        combinator_parser(ShiftLeft),
+        combinator_parser(ShiftRight),
+        combinator_parser(Sign),
+        // rationals
+        combinator_parser(Ceiling),
+        combinator_parser(Denominator),
+        combinator_parser(Floor),
+        combinator_parser(Numerator),
+        combinator_parser(Round),
         // boolean logic
         combinator_parser(And),
         combinator_parser(ExclusiveOr),
         combinator_parser(Not),
         combinator_parser(Or),
         // comparison
+        combinator_parser(Compare),
         combinator_parser(Equality),
         combinator_parser(GreaterThan),
+        combinator_parser(Identical),
         combinator_parser(LessThan),
         // functional
         combinator_parser(Apply),
+        combinator_parser(Bi),
+        combinator_parser(Cleave),
         combinator_parser(Compose),
+        combinator_parser(Cond),
+        combinator_parser(Curry),
         combinator_parser(Deep),
+        combinator_parser(Dip),
         combinator_parser(Branch),
+        combinator_parser(Keep),
+        combinator_parser(Match),
+        combinator_parser(Spread),
+        combinator_parser(Tri),
         combinator_parser(Under),
+        // continuations
+        combinator_parser(Reset),
+        combinator_parser(Resume),
+        combinator_parser(Shift),
+        // error handling
+        combinator_parser(Throw),
+        combinator_parser(Try),
+        // iteration
+        combinator_parser(Each),
+        combinator_parser(Times),
+        combinator_parser(Until),
+        combinator_parser(While),
+        // bytes
+        combinator_parser(Base64ToBytes),
+        combinator_parser(BytesToBase64),
+        combinator_parser(BytesToHexadecimal),
+        combinator_parser(BytesToList),
+        combinator_parser(DecodeBigEndian),
+        combinator_parser(DecodeLittleEndian),
+        combinator_parser(EncodeBigEndian),
+        combinator_parser(EncodeLittleEndian),
+        combinator_parser(HexadecimalToBytes),
+        combinator_parser(ListToBytes),
+        // maps
+        combinator_parser(Entries),
+        combinator_parser(Get),
+        combinator_parser(Has),
+        combinator_parser(Keys),
+        combinator_parser(MapValues),
+        combinator_parser(Put),
+        combinator_parser(Remove),
+        combinator_parser(Values),
+        // lazy sequences
+        combinator_parser(DropWhile),
+        combinator_parser(Force),
+        combinator_parser(Iterate),
+        combinator_parser(LazyFilter),
+        combinator_parser(LazyMap),
+        combinator_parser(Next),
+        combinator_parser(Take),
+        combinator_parser(TakeWhile),
         // lists
         combinator_parser(Append),
         combinator_parser(Length),
+        combinator_parser(All),
+        combinator_parser(Any),
+        combinator_parser(Count),
+        combinator_parser(Enumerate),
         combinator_parser(Filter),
+        combinator_parser(First),
+        combinator_parser(Flatten),
         combinator_parser(Fold),
         combinator_parser(Index),
+        combinator_parser(Iota),
         combinator_parser(Join),
+        combinator_parser(Last),
         combinator_parser(Map),
+        combinator_parser(Maximum),
+        combinator_parser(Minimum),
+        combinator_parser(Pack),
+        combinator_parser(Product),
+        combinator_parser(Range),
+        combinator_parser(RangeStep),
+        combinator_parser(Repeat),
+        combinator_parser(Rest),
+        combinator_parser(Reverse),
+        combinator_parser(Slice),
+        combinator_parser(Sort),
+        combinator_parser(SortBy),
+        combinator_parser(SplitAt),
+        combinator_parser(Sum),
+        combinator_parser(Uncons),
+        combinator_parser(Unique),
+        combinator_parser(Unpack),
+        combinator_parser(Zip),
         // stack manipulation
         combinator_parser(Copy),
         combinator_parser(Drop),
@@ -148,6 +348,8 @@ fn list(input: &Text) -> ParseResult<UnresolvedTerm> {
                 match term {
                     UnresolvedTerm::Resolved (Term::Data (_)) => continue,
                     UnresolvedTerm::UnresolvedLambda (_) => continue,
+                    UnresolvedTerm::UnresolvedList (_) => continue,
+                    UnresolvedTerm::UnresolvedMap (_) => continue,
                     _ => return ModeResult::Failure((), vec![])
                 }
             }
@@ -159,16 +361,238 @@ fn list(input: &Text) -> ParseResult<UnresolvedTerm> {
 }
 
 
-/// Parses an integer term
-fn integer(input: &Text) -> ParseResult<UnresolvedTerm> {
+/// Parses a map term
+fn map(input: &Text) -> ParseResult<UnresolvedTerm> {
+    match delimited(
+        token("{").then(whitespace().or_not()),
+        UnresolvedTerm::parse_many,
+        whitespace().or_not().then(token("}"))
+    )
+        .parse(input) {
+        ModeResult::Success (terms, _) => {
+            if terms.len() % 2 != 0 { return ModeResult::Failure ((), vec![]) }
+            for term in terms.clone() {
+                match term {
+                    UnresolvedTerm::Resolved (Term::Data (_)) => continue,
+                    UnresolvedTerm::UnresolvedLambda (_) => continue,
+                    UnresolvedTerm::UnresolvedList (_) => continue,
+                    UnresolvedTerm::UnresolvedMap (_) => continue,
+                    _ => return ModeResult::Failure((), vec![])
+                }
+            }
+            ModeResult::Success (UnresolvedTerm::UnresolvedMap (terms), vec![])
+        }
+        _ => ModeResult::Failure((), vec![])
+    }
+}
+
+
+/// Parses a float term, which is written with an exponent such as `1.5e3` to distinguish it from
+/// a rational, or as the word `inf` for infinity
+fn float(input: &Text) -> ParseResult<UnresolvedTerm> {
+    // infinity must match a whole word, so that it is not confused with identifiers that start
+    // with the same letters
+    let infinity = preceded(nothing(), |input: &Text| match word.parse(input) {
+        ModeResult::Success (word, messages) if word == "inf" => ModeResult::Success (
+            UnresolvedTerm::Resolved (Term::Data (Value::Float (Float::new(f64::INFINITY)))),
+            messages
+        ),
+        _ => ModeResult::Failure ((), vec![]),
+    });
+    if let ModeResult::Success (term, messages) = infinity.parse(input) {
+        return ModeResult::Success (term, messages);
+    }
     choice([
         token("-").then(number()),
         token("").then(number()),
     ])
-        //.trace("`integer` parser function")
-        .map(|(sign, number)| UnresolvedTerm::Resolved (Term::Data (Value::Integer (
-            Integer::from_string(&format!("{}{}", sign, number))
-                .expect("Parser will never parse an invalid integer")
+        .then(preceded(token("."), number()).or_not())
+        .then(preceded(token("e"), choice([
+            token("-").then(number()),
+            token("").then(number()),
+        ])))
+        .map(|(((sign, whole), fraction), (exponent_sign, exponent))| {
+            let float: String = format!(
+                "{}{}.{}e{}{}",
+                sign,
+                whole,
+                fraction.unwrap_or("0"),
+                exponent_sign,
+                exponent
+            );
+            UnresolvedTerm::Resolved (Term::Data (Value::Float (Float::from_string(&float)
+                .expect("Parser will never parse an invalid float"))))
+        })
+        .parse(input)
+}
+
+
+/// Parses an integer term, written in decimal, or in hexadecimal, octal or binary with a `0x`, `0o`
+/// or `0b` prefix, with optional underscores between digits
+fn integer(input: &Text) -> ParseResult<UnresolvedTerm> {
+    match numeric_word.parse(input) {
+        ModeResult::Success (literal, _) => match Integer::from_literal(&literal) {
+            Ok (integer) => ModeResult::Success (
+                UnresolvedTerm::Resolved (Term::Data (Value::Integer (integer))),
+                vec![]
+            ),
+            Err (_) => ModeResult::Failure ((), vec![]),
+        },
+        _ => ModeResult::Failure ((), vec![])
+    }
+}
+
+
+/// Returns whether a name is parsed as a combinator or a literal rather than as the application of
+/// a function, so that a function with that name could never be called
+pub fn is_reserved_word(name: &str) -> bool {
+    let input: Text = Text::from_string(name);
+    let result: ParseResult<UnresolvedTerm> = UnresolvedTerm::parse
+        .then_ignore(end())
+        .parse(&input);
+    match result {
+        ModeResult::Success (UnresolvedTerm::UnresolvedApplication (_), _) => false,
+        ModeResult::Success (_, _) => true,
+        _ => false,
+    }
+}
+
+
+/// Returns messages describing every malformed number literal in Compose source, so that they can
+/// be reported when the source cannot be parsed
+pub fn literal_errors(source: &str) -> Vec<String> {
+    source.split(|c: char| c.is_whitespace() || "()[]{}".contains(c))
+        .filter(|word| word.trim_start_matches('-').starts_with(|c: char| c.is_ascii_digit()))
+        .filter(|word| {
+            let text: Text = Text::from_string(word);
+            let parsed: bool = matches!(
+                choice([float, rational, integer]).then_ignore(end()).parse(&text),
+                ModeResult::Success (_, _)
+            );
+            !parsed
+        })
+        .filter_map(|word| Integer::from_literal(word).err())
+        .collect()
+}
+
+
+/// Parses a word that starts with a digit, made of the letters, digits and underscores that can
+/// appear in an integer literal, with an optional sign
+fn numeric_word(input: &Text) -> ParseResult<String> {
+    choice([token("-"), token("")])
+        .then(number())
+        .then(many(choice((
+            number(),
+            choice((unicode_identifier(), token("_"))),
+        ))))
+        .map(|((sign, head), tail): ((&str, &str), Vec<&str>)| {
+            let mut word: String = format!("{}{}", sign, head);
+            for segment in tail {
+                word.push_str(segment);
+            }
+            word
+        })
+        .parse(input)
+}
+
+
+/// Parses a rational term, written either as a fraction such as `3/4` or as a decimal such as
+/// `1.25`
+fn rational(input: &Text) -> ParseResult<UnresolvedTerm> {
+    fn signed_number(input: &Text) -> ParseResult<String> {
+        choice([
+            token("-").then(number()),
+            token("").then(number()),
+        ])
+            .map(|(sign, number)| format!("{}{}", sign, number))
+            .parse(input)
+    }
+    match choice((
+        signed_number.then(preceded(token("/"), number()))
+            .map(|(numerator, denominator)| Rational::from_fraction(
+                Integer::from_string(&numerator)
+                    .expect("Parser will never parse an invalid integer"),
+                Integer::from_string(denominator)
+                    .expect("Parser will never parse an invalid integer"),
+            )),
+        signed_number.then(preceded(token("."), number()))
+            .map(|(whole, fraction)| Rational::from_decimal(&format!("{}.{}", whole, fraction))),
+    ))
+        .parse(input) {
+        ModeResult::Success (Some (rational), _) => ModeResult::Success (
+            UnresolvedTerm::Resolved (Term::Data (Value::from_rational(rational))),
+            vec![]
+        ),
+        _ => ModeResult::Failure ((), vec![])
+    }
+}
+
+
+/// Parses a word made of identifiers joined by the symbols used in the names of combinators, such
+/// as `drop-while` or `has?`
+fn word(input: &Text) -> ParseResult<String> {
+    unicode_identifier()
+        .then(many(choice((
+            choice([token("-"), token("?"), token(">"), token("!")]),
+            unicode_identifier(),
         ))))
+        .map(|(head, tail): (&str, Vec<&str>)| {
+            let mut word: String = head.to_string();
+            for segment in tail {
+                word.push_str(segment);
+            }
+            word
+        })
         .parse(input)
 }
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        Namespace,
+        testing::{
+            define,
+            evaluate_bare,
+            evaluate_in,
+        },
+    };
+    use super::literal_errors;
+
+    #[test]
+    fn inf_is_parsed_as_an_infinite_float() {
+        assert_eq!(evaluate_bare("inf inf ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("inf 1.5e3"), Ok ("inf 1.5e3".to_string()));
+    }
+
+    #[test]
+    fn inf_is_not_parsed_at_the_start_of_names() {
+        let mut namespace: Namespace = Namespace::new();
+        define(&mut namespace, "info : 1 ;");
+        assert_eq!(evaluate_in(&mut namespace, "info inf"), Ok ("1 inf".to_string()));
+    }
+
+
+    #[test]
+    fn prefixed_and_underscored_integer_literals_are_parsed() {
+        assert_eq!(
+            evaluate_bare("0xFF_FF 0o17 0b1010 1_000_000 -0x10"),
+            Ok ("65535 15 10 1000000 -16".to_string())
+        );
+    }
+
+    #[test]
+    fn malformed_literals_are_reported() {
+        assert_eq!(
+            literal_errors("1 0xZZ ( 1__0 ) 2/3 1.5e3 name"),
+            vec![
+                "Malformed integer literal `0xZZ`: `Z` is not a hexadecimal digit".to_string(),
+                "Malformed integer literal `1__0`: underscores can only be placed between digits"
+                    .to_string(),
+            ]
+        );
+    }
+
+}
@@ -0,0 +1,104 @@
+// Copyright Rob Gage 2025
+
+use super::{
+    DeclarationError,
+    first_duplicate,
+    UnresolvedFunction,
+    UnresolvedTerm,
+};
+use crate::{
+    RecordOperation,
+    RecordType,
+    Term,
+    UnionType,
+};
+use pups::*;
+use std::sync::Arc;
+
+/// A union declaration such as `union option none some(x) ;`, which defines the functions of a
+/// record type for each of its variants, so that values of the union can be dispatched on with
+/// `match`
+#[derive(Clone, Debug)]
+pub struct UnresolvedUnion {
+    /// The name of the union
+    name: String,
+    /// The names of the variants of the union with the names of their fields, in order
+    variants: Vec<(String, Vec<String>)>,
+}
+
+impl UnresolvedUnion {
+
+    /// Returns the `UnresolvedFunction`s defined by this `UnresolvedUnion`
+    pub fn functions(&self) -> Vec<UnresolvedFunction> {
+        let union: Arc<UnionType> = Arc::new(UnionType::new(
+            self.name.clone(),
+            self.variants.iter().map(|(name, _)| name.clone()).collect(),
+        ));
+        self.variants.iter()
+            .enumerate()
+            .flat_map(|(index, (name, fields))| RecordOperation::declared(
+                RecordType::variant(name.clone(), fields.clone(), union.clone(), index)
+            ))
+            .map(|operation| UnresolvedFunction::new(
+                operation.name(),
+                vec![UnresolvedTerm::Resolved (Term::Record (operation))],
+            ))
+            .collect()
+    }
+
+    /// Returns the name of the union declared by this `UnresolvedUnion`
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns a `DeclarationError` if this `UnresolvedUnion` repeats a variant name, or a field
+    /// name within a variant
+    pub fn validate(&self) -> Result<(), DeclarationError> {
+        if let Some (variant) = first_duplicate(self.variants.iter().map(|(name, _)| name)) {
+            return Err (DeclarationError::duplicate(&self.name, variant));
+        }
+        for (variant, fields) in &self.variants {
+            if let Some (field) = first_duplicate(fields.iter()) {
+                return Err (DeclarationError::duplicate(variant, field));
+            }
+        }
+        Ok (())
+    }
+
+    /// Parses an `UnresolvedUnion` from text, failing if it has no variants, and including one that
+    /// repeats a name, which `UnresolvedUnion::validate` reports
+    pub fn parse(input: &Text) -> ParseResult<Self> {
+        match preceded(
+            token("union").then(whitespace()),
+            unicode_identifier()
+                .then(many(preceded(whitespace(), variant)))
+                .then_ignore(whitespace().or_not().then(token(";")))
+        )
+            .parse(input) {
+            ModeResult::Success ((name, variants), messages) => {
+                if variants.is_empty() {
+                    return ModeResult::Failure ((), vec![]);
+                }
+                ModeResult::Success (Self { name: name.to_string(), variants }, messages)
+            }
+            _ => ModeResult::Failure ((), vec![]),
+        }
+    }
+
+}
+
+
+
+/// Parses a variant of a union, made of its name and optionally the names of its fields between
+/// parentheses, such as `some(x)`
+fn variant(input: &Text) -> ParseResult<(String, Vec<String>)> {
+    unicode_identifier()
+        .then(delimited(
+            token("(").then(whitespace().or_not()),
+            separated(unicode_identifier(), whitespace()),
+            whitespace().or_not().then(token(")"))
+        ).or_not())
+        .map(|(name, fields): (&str, Option<Vec<&str>>)| (
+            name.to_string(),
+            fields.unwrap_or_default().into_iter().map(str::to_string).collect(),
+        ))
+        .parse(input)
+}
@@ -3,11 +3,17 @@
 use crate::{
     Combinator,
     FunctionReference,
+    RecordOperation,
     Value,
 };
+use std::{
+    cmp::Ordering,
+    ops::Deref,
+    sync::Arc,
+};
 
 /// A concatenative programming term that can represent data or operations
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Term
 where
     Self: Sized
@@ -16,12 +22,73 @@ where
     /// Application of a named function
     Application (FunctionReference),
 
+    /// Pops a value off the stack for each name (second field) and binds them to the local slots
+    /// of the current frame, starting at a slot index (first field), where the names are only kept
+    /// so that the term can be displayed
+    Bind (usize, LocalNames),
+
+    /// Creates a lambda that captures the values in the listed local slots of the current frame
+    Closure (FunctionReference, Vec<usize>),
+
     /// A combinator that performs an operation on the stack
     Combinator (Combinator),
 
     /// Data as a term that will be pushed to the `Stack`
     Data (Value),
 
+    /// Pushes the value bound to a local slot of the current frame
+    Local (usize),
+
+    /// An operation on records generated by a record declaration
+    Record (RecordOperation),
+
     Recursion,
 
-}
\ No newline at end of file
+    /// Discards the local slots of the current frame from a slot index onwards
+    Unbind (usize),
+
+}
+
+
+
+/// The names of the locals bound by a `Term::Bind`, which are only kept so that the term can be
+/// displayed, so names of the same length are equal and bodies that differ only in the names of
+/// their locals are equal and interned once
+#[derive(Clone, Debug)]
+pub struct LocalNames (Arc<[String]>);
+
+impl Deref for LocalNames {
+
+    type Target = [String];
+
+    fn deref(&self) -> &[String] { &self.0 }
+
+}
+
+impl Eq for LocalNames {}
+
+impl FromIterator<String> for LocalNames {
+
+    fn from_iter<I: IntoIterator<Item = String>>(names: I) -> Self {
+        Self (names.into_iter().collect())
+    }
+
+}
+
+impl Ord for LocalNames {
+
+    fn cmp(&self, other: &Self) -> Ordering { self.0.len().cmp(&other.0.len()) }
+
+}
+
+impl PartialEq for LocalNames {
+
+    fn eq(&self, other: &Self) -> bool { self.0.len() == other.0.len() }
+
+}
+
+impl PartialOrd for LocalNames {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+
+}
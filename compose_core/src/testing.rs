@@ -0,0 +1,108 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    FunctionReference,
+    Namespace,
+    UnresolvedFunction,
+    UnresolvedRecord,
+    UnresolvedUnion,
+    Value,
+};
+use pups::*;
+use std::{
+    fs::{
+        create_dir_all,
+        write,
+    },
+    path::PathBuf,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+/// The number of directories created by `source_directory`, used to give each one a unique path
+static SOURCE_DIRECTORIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Defines a function, record or union in a `Namespace` from its source, panicking if it cannot be
+/// parsed, validated or resolved
+pub fn define(namespace: &mut Namespace, source: &str) {
+    let input: Text = Text::from_string(source);
+    let functions: Vec<UnresolvedFunction>
+        = if let ParseResult::Success (record, _) = UnresolvedRecord::parse
+            .then_ignore(end())
+            .parse(&input) {
+        record.validate().unwrap_or_else(|error| panic!("`{}` is invalid: {}", source, error));
+        record.functions()
+    } else if let ParseResult::Success (union, _) = UnresolvedUnion::parse
+        .then_ignore(end())
+        .parse(&input) {
+        union.validate().unwrap_or_else(|error| panic!("`{}` is invalid: {}", source, error));
+        union.functions()
+    } else if let ParseResult::Success (function, _) = UnresolvedFunction::parse
+        .then_ignore(end())
+        .parse(&input) {
+        vec![function]
+    } else { panic!("`{}` is not a valid definition", source) };
+    for function in functions {
+        if let Err (error) = namespace.define(&function) {
+            panic!("`{}` cannot be defined: {}", source, error);
+        }
+    }
+}
+
+/// Evaluates free terms in a new `Namespace` with the standard prelude
+pub fn evaluate(source: &str) -> Result<String, String> {
+    evaluate_in(&mut Namespace::with_prelude(), source)
+}
+
+/// Evaluates free terms in a `Namespace`, returning the values left on the stack as they are
+/// displayed, separated by spaces, or the message of the error that was raised
+pub fn evaluate_in(namespace: &mut Namespace, source: &str) -> Result<String, String> {
+    let input: Text = Text::from_string(source);
+    let ParseResult::Success (function, _) = UnresolvedFunction::parse_free_terms
+        .then_ignore(end())
+        .parse(&input)
+    else { panic!("`{}` is not valid free terms", source) };
+    let reference: FunctionReference = namespace.define(&function)
+        .unwrap_or_else(|error| panic!("`{}` cannot be defined: {}", source, error));
+    let mut virtual_machine = namespace.create_virtual_machine();
+    match virtual_machine.evaluate(reference) {
+        Ok (_) => {
+            let mut printed: Vec<String> = Vec::new();
+            for value in virtual_machine.data() {
+                let mut written: String = String::new();
+                namespace.write_value(&mut written, &value).unwrap();
+                printed.push(written);
+            }
+            Ok (printed.join(" "))
+        }
+        Err (Value::Error (message)) => Err (message),
+        Err (value) => {
+            let mut written: String = String::new();
+            namespace.write_value(&mut written, &value).unwrap();
+            Err (written)
+        }
+    }
+}
+
+/// Evaluates free terms in a new `Namespace` without the standard prelude
+pub fn evaluate_bare(source: &str) -> Result<String, String> {
+    evaluate_in(&mut Namespace::new(), source)
+}
+
+/// Creates a new temporary directory containing source files, given by their paths relative to the
+/// directory and their contents
+pub fn source_directory(files: &[(&str, &str)]) -> PathBuf {
+    let directory: PathBuf = std::env::temp_dir().join(format!(
+        "compose-{}-{}",
+        std::process::id(),
+        SOURCE_DIRECTORIES.fetch_add(1, Ordering::Relaxed)
+    ));
+    for (path, source) in files {
+        let path: PathBuf = directory.join(path);
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(path, source).unwrap();
+    }
+    directory
+}
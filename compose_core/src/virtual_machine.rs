@@ -2,27 +2,44 @@
 
 pub mod data;
 pub mod combinator;
+mod bytecode;
 mod control;
+mod record;
+mod sequence;
 
 use control::{
     ControlAction,
     ControlFrame,
     ControlStack,
 };
+
+pub use control::Continuation;
 use data::DataStack;
 use crate::{
     Environment,
     Function,
     FunctionReference,
 };
-use std::sync::{
-    Arc,
-    RwLock,
-    RwLockReadGuard,
+use std::{
+    mem,
+    sync::{
+        Arc,
+        RwLock,
+        RwLockReadGuard,
+    },
 };
 
+pub use bytecode::Instruction;
 pub use combinator::Combinator;
 pub use data::Value;
+pub use record::{
+    Record,
+    RecordOperation,
+    RecordType,
+    UnionType,
+};
+pub use sequence::Sequence;
+use sequence::Pull;
 
 /// A virtual machine used for evaluation of Compose programs and functions
 pub struct VirtualMachine {
@@ -32,42 +49,61 @@ pub struct VirtualMachine {
 
 impl VirtualMachine {
 
-    pub fn evaluate(&mut self, function_reference: FunctionReference) -> Result<(), String> {
+    /// Evaluates a function, returning the error value if an error is raised and not handled
+    pub fn evaluate(&mut self, function_reference: FunctionReference) -> Result<(), Value> {
         // lock environment so nothing can write to it until evaluation is finished
         let guard: RwLockReadGuard<Environment> = self.environment.read().unwrap();
         let environment: &Environment = &*guard;
-        // create control stack, then push first function to the control stack as a `ControlFrame`
+        // create control stack, then evaluate frames from the first function's frame until the
+        // control stack is empty
         let mut control_stack: ControlStack = ControlStack::new();
         let function: Function = function_reference.get(environment);
-        control_stack.push_frame(ControlFrame::from_function(function));
-        // repeatedly pop frame from stack and do as much evaluation as possible
-        while let Some (mut frame) = control_stack.pop_frame() {
-            loop {
-                let action = frame.execute_step(&mut self.data_stack, environment);
-                match action {
-                    ControlAction::Continue => continue,
-                    ControlAction::Error(error) => return Err(error),
-                    // ControlAction::FoldList { start, list, function } => {
-                    //     todo!()
-                    // },
-                    // ControlAction::FilterList { list, function } => {
-                    //     todo!()
-                    // },
-                    // ControlAction::MapList { list, function } => {
-                    //     for value in list {
-                    //         
-                    //     }
-                    // }
-                    ControlAction::Pop => break,
-                    ControlAction::Push(function) => {
-                        control_stack.push_frame(frame);
-                        control_stack.push_frame(ControlFrame::from_function(function));
-                        break;
-                    },
-                }
+        let mut frame: ControlFrame = ControlFrame::from_function(function, environment);
+        loop {
+            match frame.execute_step(&mut self.data_stack, environment) {
+                ControlAction::Continue => continue,
+                // pushed frames are evaluated right away, and replace frames that have finished
+                ControlAction::PushFrame (pushed) => {
+                    push_or_replace(&mut control_stack, &mut frame, pushed);
+                    continue;
+                },
+                ControlAction::Push (function) => {
+                    let pushed: ControlFrame = ControlFrame::from_function(function, environment);
+                    push_or_replace(&mut control_stack, &mut frame, pushed);
+                    continue;
+                },
+                ControlAction::Pop => {},
+                ControlAction::Error (error) => control_stack
+                    .unwind(&mut self.data_stack, environment, Value::Error (error))?,
+                ControlAction::Raise (error) =>
+                    control_stack.unwind(&mut self.data_stack, environment, error)?,
+                ControlAction::Resume (continuation) => {
+                    control_stack.push_frame(frame);
+                    control_stack.resume(&mut self.data_stack, &continuation);
+                },
+                ControlAction::Shift (function) => {
+                    control_stack.push_frame(frame);
+                    match control_stack.capture(&mut self.data_stack) {
+                        Some (continuation) => {
+                            self.data_stack.push(Value::Continuation (continuation));
+                            control_stack
+                                .push_frame(ControlFrame::from_function(function, environment));
+                        }
+                        None => control_stack.unwind(
+                            &mut self.data_stack,
+                            environment,
+                            Value::Error ("Cannot perform `shift` outside of a `reset`"
+                                .to_string())
+                        )?,
+                    }
+                },
+            }
+            // continue with the frame on top of the control stack
+            match control_stack.pop_frame() {
+                Some (next) => frame = next,
+                None => return Ok (()),
             }
         }
-        Ok (())
     }
 
     /// Creates a new `VirtualMachine` from a `&Arc<RwLock<Environment>>`
@@ -92,4 +128,17 @@ impl VirtualMachine {
         self
     }
 
-}
\ No newline at end of file
+}
+
+
+
+/// Makes a pushed `ControlFrame` the current one, saving the current frame on the `ControlStack`
+/// unless it has finished
+fn push_or_replace<'a>(
+    control_stack: &mut ControlStack<'a>,
+    frame: &mut ControlFrame<'a>,
+    pushed: ControlFrame<'a>,
+) {
+    let previous: ControlFrame = mem::replace(frame, pushed);
+    if !previous.is_finished() { control_stack.push_frame(previous); }
+}
@@ -0,0 +1,259 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Environment,
+    FunctionReference,
+    Value,
+};
+use super::{
+    Combinator,
+    ControlAction,
+    ControlFrame,
+    DataStack,
+    control::{
+        apply_term,
+        bind_locals,
+    },
+};
+
+/// An instruction that a `Term` stored in an `Environment` is lowered into, with its operands
+/// inlined, so that functions can be evaluated without matching on `Term`s or copying them
+#[derive(Clone, Copy, Debug)]
+pub enum Instruction {
+
+    /// Pops a number of values (second field) off the stack and binds them to the local slots of
+    /// the current frame, starting at a slot index (first field)
+    Bind (u32, u32),
+
+    /// Pushes a boolean to the stack
+    Boolean (bool),
+
+    /// Applies a stored function, whose code is found when it is called so that it is always the
+    /// code of the function's current body
+    Call (FunctionReference),
+
+    /// A combinator that performs an operation on the stack
+    Combinator (Combinator),
+
+    /// Pushes the value at an index of the `Environment`'s constant pool to the stack
+    Constant (u32),
+
+    /// Pushes the value bound to a local slot of the current frame
+    Local (u32),
+
+    /// Applies the function being evaluated again
+    Recursion,
+
+    /// Evaluates the `Term` at the same index of the `Environment`'s term buffer, for terms whose
+    /// operands are too large to be inlined
+    Term,
+
+    /// Discards the local slots of the current frame from a slot index onwards
+    Unbind (u32),
+
+}
+
+/// Executes the code of a function from a program counter until it ends, or until an
+/// `Instruction` needs the `ControlStack` to be changed
+///
+/// A call or recursion in tail position replaces the function being executed instead of pushing a
+/// new `ControlFrame`, so that tail recursion runs in constant space.
+pub fn execute<'a>(
+    start: &mut usize,
+    end: &mut usize,
+    counter: &mut usize,
+    locals: &mut Vec<Value>,
+    data_stack: &mut DataStack,
+    environment: &'a Environment,
+) -> ControlAction<'a> {
+    while *counter < *end {
+        let index: usize = *counter;
+        *counter += 1;
+        let action: ControlAction = match environment.instruction(index) {
+            Instruction::Bind (slot, count) =>
+                bind_locals(slot as usize, count as usize, locals, data_stack),
+            Instruction::Boolean (boolean) => {
+                data_stack.push(Value::Boolean (boolean));
+                continue;
+            }
+            Instruction::Call (reference) => {
+                let (callee_start, callee_end): (usize, usize) = reference.slice(environment);
+                if *counter == *end {
+                    *start = callee_start;
+                    *end = callee_end;
+                    *counter = *start;
+                    locals.clear();
+                    continue;
+                } else {
+                    ControlAction::PushFrame (ControlFrame::Execution {
+                        counter: callee_start,
+                        end: callee_end,
+                        locals: Vec::new(),
+                        start: callee_start,
+                    })
+                }
+            }
+            Instruction::Combinator (combinator) => combinator.evaluate(data_stack, environment),
+            Instruction::Constant (constant) => {
+                data_stack.push(environment.constant(constant as usize).clone());
+                continue;
+            }
+            Instruction::Local (slot) => {
+                data_stack.push(locals[slot as usize].clone());
+                continue;
+            }
+            Instruction::Recursion => if *counter == *end {
+                *counter = *start;
+                locals.clear();
+                continue;
+            } else {
+                ControlAction::PushFrame (ControlFrame::Execution {
+                    counter: *start,
+                    end: *end,
+                    locals: Vec::new(),
+                    start: *start,
+                })
+            },
+            Instruction::Term =>
+                apply_term(environment.term(index), locals, data_stack, environment),
+            Instruction::Unbind (slot) => {
+                locals.truncate(slot as usize);
+                continue;
+            }
+        };
+        if !matches!(action, ControlAction::Continue) { return action; }
+    }
+    ControlAction::Pop
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        Environment,
+        FunctionReference,
+        Integer,
+        Namespace,
+        Term,
+        Value,
+        VirtualMachine,
+        testing::{
+            define,
+            evaluate_in,
+        },
+    };
+    use std::sync::{
+        Arc,
+        RwLock,
+    };
+
+    /// The definitions used by the programs in `CORPUS`
+    const DEFINITIONS: [&str; 7] = [
+        "factorial : copy 2 < ( drop 1 ) ( copy 1 - factorial * ) ? ;",
+        "countdown : copy 0 = ( ) ( 1 - countdown ) ? ;",
+        "plus_one : 1 + ;",
+        "twice : plus_one plus_one ;",
+        "hypotenuse_squared : [a b] -> ( a a * b b * + ) ;",
+        "record point x y ;",
+        "union option none some(x) ;",
+    ];
+
+    /// Programs run on both the bytecode path and the term path, with the values they leave on the
+    /// stack or the message of the error they raise
+    const CORPUS: [(&str, Result<&str, &str>); 41] = [
+        ("5 factorial 0 factorial", Ok ("120 1")),
+        ("100000 countdown", Ok ("0")),
+        ("3 twice", Ok ("5")),
+        ("3 4 hypotenuse_squared", Ok ("25")),
+        ("1 2 + 3 * 4 -", Ok ("5")),
+        ("7 2 / 7 2 %", Ok ("7/2 1")),
+        ("1 0 /", Err ("Cannot perform `/` with a divisor of zero")),
+        ("[ 1 2 3 ] ( 2 * ) map", Ok ("[ 2 4 6 ]")),
+        ("[ 1 2 3 4 ] ( even ) filter", Ok ("[ 2 4 ]")),
+        ("0 [ 1 2 3 ] ( + ) fold", Ok ("6")),
+        ("[ 3 1 2 ] sort", Ok ("[ 1 2 3 ]")),
+        ("0 10 ( 1 + ) times", Ok ("10")),
+        ("0 ( copy 5 < ) ( 1 + ) while", Ok ("5")),
+        ("3 [n] -> ( ( n + ) ) 4 swap apply", Ok ("7")),
+        ("( 1 ) ( 2 + ) compose apply", Ok ("3")),
+        ("5 ( + ) curry 2 swap apply", Ok ("7")),
+        ("1 2 point point_y", Ok ("2")),
+        ("1 2 point 5 set_point_x", Ok ("point { x: 5 y: 2 }")),
+        ("5 some ( 0 ) ( plus_one ) match", Ok ("6")),
+        ("none ( 0 ) ( plus_one ) match", Ok ("0")),
+        ("5 ( 1 + ) ( 2 * ) bi", Ok ("6 10")),
+        ("1 2 [ ( 10 + ) ( 20 * ) ] spread", Ok ("11 40")),
+        ("5 ( plus_one ) keep", Ok ("6 5")),
+        ("1 2 ( plus_one ) dip", Ok ("2 2")),
+        ("5 [ [ ( 0 < ) ( drop -1 ) ] [ ( 0 = ) ( drop 0 ) ] ] ( drop 1 ) cond", Ok ("1")),
+        (
+            "1 2 ( drop drop 1 0 / ) ( ) try",
+            Ok ("1 2 error(\"Cannot perform `/` with a divisor of zero\")"),
+        ),
+        ("( 1 ( ) shift 2 ) reset resume", Ok ("1 2")),
+        ("0 inf range ( square ) lazy-map 4 take force", Ok ("[ 0 1 4 9 ]")),
+        ("[ 1 2 3 ] ( plus_one ) each", Ok ("2 3 4")),
+        ("{ 1 2 3 4 } 5 6 put keys", Ok ("[ 1 3 5 ]")),
+        ("[ 1 2 3 ] uncons", Ok ("1 [ 2 3 ]")),
+        ("1.5e0 2.5e0 +", Ok ("4e0")),
+        ("1 3 / 1 6 / +", Ok ("1/2")),
+        ("#[ 01 02 ] #[ 03 ] join", Ok ("#[ 01 02 03 ]")),
+        ("( 1 2 ) ( 1 ) ( 2 ) compose =", Ok ("true")),
+        ("1 drop drop", Err ("No items in the stack to be dropped")),
+        ("( plus_one ) 3 swap apply", Ok ("4")),
+        ("plus_one", Err ("Not enough items in the stack to perform arithmetic operation")),
+        ("[ 1 2 ] [ 3 ] join length", Ok ("3")),
+        (
+            "5 some ( plus_one ) match",
+            Err ("Cannot perform `match` on a value of the union `option` unless there is one \
+            lambda above it for each of its variants: none, some"),
+        ),
+        ("[ ] first", Err ("Cannot perform `first` on an empty list")),
+    ];
+
+    /// Creates a `Namespace` with the standard prelude and the definitions in `DEFINITIONS`, which
+    /// executes bytecode or evaluates terms
+    fn namespace(bytecode: bool) -> Namespace {
+        let mut namespace: Namespace = Namespace::with_prelude();
+        namespace.set_bytecode(bytecode);
+        for definition in DEFINITIONS {
+            define(&mut namespace, definition);
+        }
+        namespace
+    }
+
+    #[test]
+    fn bytecode_and_terms_have_the_same_results() {
+        let mut bytecode: Namespace = namespace(true);
+        let mut terms: Namespace = namespace(false);
+        for (program, expected) in CORPUS {
+            let expected: Result<String, String> = expected
+                .map(str::to_string)
+                .map_err(str::to_string);
+            assert_eq!(evaluate_in(&mut bytecode, program), expected, "bytecode: `{}`", program);
+            assert_eq!(evaluate_in(&mut terms, program), expected, "terms: `{}`", program);
+        }
+    }
+
+    /// Creates a `Term` that pushes an integer
+    fn pushed(integer: usize) -> Term { Term::Data (Value::Integer (Integer::from_usize(integer))) }
+
+    #[test]
+    fn calls_run_the_current_body_of_their_function() {
+        let mut environment: Environment = Environment::new();
+        let callee: FunctionReference = FunctionReference::reserve(&mut environment);
+        let caller: FunctionReference = FunctionReference::reserve(&mut environment);
+        caller.set_body(&mut environment, &[Term::Application (callee), pushed(1)]);
+        environment.compile();
+        // the callee's body is set after the call to it has been lowered
+        callee.set_body(&mut environment, &[pushed(2)]);
+        environment.compile();
+        let mut virtual_machine: VirtualMachine
+            = VirtualMachine::from_environment(&Arc::new(RwLock::new(environment)));
+        virtual_machine.evaluate(caller).unwrap();
+        let data: Vec<Term> = virtual_machine.data().into_iter().map(Term::Data).collect();
+        assert_eq!(data, vec![pushed(2), pushed(1)]);
+    }
+
+}
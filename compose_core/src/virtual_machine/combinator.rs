@@ -1,21 +1,44 @@
 // Copyright Rob Gage 2025
 
 use crate::{
+    encoding::{
+        decode_base64,
+        decode_hexadecimal,
+        encode_base64,
+        encode_hexadecimal,
+    },
+    Float,
+    LambdaReference,
     Value,
     Integer,
+    Rational,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    mem::swap,
+    vec::IntoIter,
 };
-use std::mem::swap;
 use crate::Term;
 use super::{
     ControlAction::{
         self,
         *
     },
+    ControlFrame,
     DataStack,
     Environment,
     Function,
+    Pull,
+    Sequence,
 };
 
+/// The greatest number of items that a list generated by a combinator such as `range` can have,
+/// so that generating an enormous list raises an error instead of exhausting memory
+const MAXIMUM_GENERATED_ITEMS: usize = 1 << 24;
+
 /// Defines `Combinator` enum
 macro_rules! combinators {
     (
@@ -26,7 +49,7 @@ macro_rules! combinators {
     ) => {
 
         /// A concatenative combinator that modifies the stack
-        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
         pub enum Combinator {
             $(
                 $variant,
@@ -76,7 +99,8 @@ combinators! {
     ///
     /// `a b -> (a / b)`
     ///
-    /// Divides the second number on top of the stack by the number on top of the stack
+    /// Divides the second number on top of the stack by the number on top of the stack exactly,
+    /// evaluating to a rational if the quotient is not a whole number
     Divide
     ; "/",
 
@@ -105,6 +129,308 @@ combinators! {
     Subtract
     ; "-",
 
+    /// # Integer Combinators
+
+    /// ## Absolute
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the number on top of the stack into its absolute value
+    Absolute
+    ; "abs",
+
+    /// ## Bit And
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the bitwise and of the two integers on top of the stack, as if they were in
+    /// two's complement
+    BitAnd
+    ; "bit-and",
+
+    /// ## Bit Length
+    ///
+    /// `# -> #`
+    ///
+    /// Turns the integer on top of the stack into the number of bits needed to represent its
+    /// absolute value
+    BitLength
+    ; "bit-length",
+
+    /// ## Bit Not
+    ///
+    /// `# -> #`
+    ///
+    /// Inverts every bit of the integer on top of the stack, as if it were in two's complement
+    BitNot
+    ; "bit-not",
+
+    /// ## Bit Or
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the bitwise or of the two integers on top of the stack, as if they were in two's
+    /// complement
+    BitOr
+    ; "bit-or",
+
+    /// ## Bit Xor
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the bitwise exclusive or of the two integers on top of the stack, as if they
+    /// were in two's complement
+    BitXor
+    ; "bit-xor",
+
+    /// ## Divide Floored
+    ///
+    /// `# # -> #`
+    ///
+    /// Divides the second integer on top of the stack by the integer on top of the stack, rounding
+    /// the quotient toward negative infinity
+    DivideFloored
+    ; "div",
+
+    /// ## Divide Modulo
+    ///
+    /// `# # -> # #`
+    ///
+    /// Divides the second integer on top of the stack by the integer on top of the stack, evaluating
+    /// to both the quotient rounded toward negative infinity and the remainder of that division
+    DivideModulo
+    ; "divmod",
+
+    /// ## Greatest Common Divisor
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the greatest common divisor of the two integers on top of the stack
+    GreatestCommonDivisor
+    ; "gcd",
+
+    /// ## Integer Square Root
+    ///
+    /// `# -> #`
+    ///
+    /// Turns the non-negative integer on top of the stack into the greatest integer whose square is
+    /// not greater than it
+    IntegerSquareRoot
+    ; "isqrt",
+
+    /// ## Least Common Multiple
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the least common multiple of the two integers on top of the stack
+    LeastCommonMultiple
+    ; "lcm",
+
+    /// ## Modulo
+    ///
+    /// `# # -> #`
+    ///
+    /// Evaluates to the remainder of dividing the second integer on top of the stack by the integer
+    /// on top of the stack with the quotient rounded toward negative infinity, which has the same
+    /// sign as the divisor
+    Modulo
+    ; "mod",
+
+    /// ## Negate
+    ///
+    /// `a -> b`
+    ///
+    /// Negates the number on top of the stack
+    Negate
+    ; "negate",
+
+    /// ## Power Modulo
+    ///
+    /// `# # # -> #`
+    ///
+    /// Raises the third integer from the top of the stack to the power of the non-negative second
+    /// integer, modulo the integer on top of the stack
+    PowerModulo
+    ; "powmod",
+
+    /// ## Quotient
+    ///
+    /// `# # -> #`
+    ///
+    /// Divides the second integer on top of the stack by the integer on top of the stack, truncating
+    /// the quotient toward zero
+    Quotient
+    ; "quot",
+
+    /// ## Shift Left
+    ///
+    /// `# # -> #`
+    ///
+    /// Shifts the bits of the second integer on top of the stack left by the non-negative integer on
+    /// top of the stack
+    ShiftLeft
+    ; "shift-left",
+
+    /// ## Shift Right
+    ///
+    /// `# # -> #`
+    ///
+    /// Shifts the bits of the second integer on top of the stack right by the non-negative integer
+    /// on top of the stack, rounding toward negative infinity
+    ShiftRight
+    ; "shift-right",
+
+    /// ## Sign
+    ///
+    /// `a -> #`
+    ///
+    /// Turns the integer or rational on top of the stack into -1, 0 or 1 depending on whether it is
+    /// negative, zero or positive
+    Sign
+    ; "sign",
+
+    /// # Rational Combinators
+
+    /// ## Ceiling
+    ///
+    /// `a -> #`
+    ///
+    /// Rounds the number on top of the stack up to the nearest integer
+    Ceiling
+    ; "ceil",
+
+    /// ## Denominator
+    ///
+    /// `a -> #`
+    ///
+    /// Turns the number on top of the stack into the denominator of its lowest terms fraction
+    Denominator
+    ; "denominator",
+
+    /// ## Floor
+    ///
+    /// `a -> #`
+    ///
+    /// Rounds the number on top of the stack down to the nearest integer
+    Floor
+    ; "floor",
+
+    /// ## Numerator
+    ///
+    /// `a -> #`
+    ///
+    /// Turns the number on top of the stack into the numerator of its lowest terms fraction
+    Numerator
+    ; "numerator",
+
+    /// ## Round
+    ///
+    /// `a -> #`
+    ///
+    /// Rounds the number on top of the stack to the nearest integer, rounding half-way cases away
+    /// from zero
+    Round
+    ; "round",
+
+    /// # Floating-Point Combinators
+
+    /// ## Arc Cosine
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack into its arccosine in radians
+    ArcCosine
+    ; "acos",
+
+    /// ## Arc Sine
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack into its arcsine in radians
+    ArcSine
+    ; "asin",
+
+    /// ## Arc Tangent
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack into its arctangent in radians
+    ArcTangent
+    ; "atan",
+
+    /// ## Cosine
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack, in radians, into its cosine
+    Cosine
+    ; "cos",
+
+    /// ## Exponential
+    ///
+    /// `a -> b`
+    ///
+    /// Raises e to the power of the float on top of the stack
+    Exponential
+    ; "exp",
+
+    /// ## Float To Integer
+    ///
+    /// `a -> #`
+    ///
+    /// Converts the float on top of the stack to an integer by truncating it toward zero
+    FloatToInteger
+    ; "float>int",
+
+    /// ## Integer To Float
+    ///
+    /// `a -> b`
+    ///
+    /// Converts the integer or rational on top of the stack to the nearest float
+    IntegerToFloat
+    ; "int>float",
+
+    /// ## Logarithm
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack into its natural logarithm
+    Logarithm
+    ; "log",
+
+    /// ## Power
+    ///
+    /// `a b -> c`
+    ///
+    /// Raises the number below the top of the stack to the power of the number on top of the stack,
+    /// which must be an integer unless both numbers are floats
+    Power
+    ; "pow",
+
+    /// ## Sine
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack, in radians, into its sine
+    Sine
+    ; "sin",
+
+    /// ## Square Root
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack into its square root
+    SquareRoot
+    ; "sqrt",
+
+    /// ## Tangent
+    ///
+    /// `a -> b`
+    ///
+    /// Turns the float on top of the stack, in radians, into its tangent
+    Tangent
+    ; "tan",
+
     /// # Boolean Logic Combinators
 
     /// ## And
@@ -143,6 +469,15 @@ combinators! {
 
     /// ## Comparison Combinators
 
+    /// ## Compare
+    ///
+    /// `a b -> #`
+    ///
+    /// Evaluates to -1, 0 or 1 depending on whether the item below the top of the stack is less
+    /// than, equal to or greater than the item on top of the stack
+    Compare
+    ; "compare",
+
     /// ## Equality
     ///
     /// `a b -> (a = b)`
@@ -156,16 +491,26 @@ combinators! {
     ///
     /// `a b -> (a > b)`
     ///
-    /// Evaluates to a true boolean value if the integer on top of the stack is less than the
+    /// Evaluates to a true boolean value if the item on top of the stack is less than the
     /// one below it.
     GreaterThan
     ; ">",
 
+    /// ## Identical
+    ///
+    /// `a b -> (a identical? b)`
+    ///
+    /// Evaluates to a true boolean value if the top two items on the stack are identical, which
+    /// for lambdas means that one is a copy of the other rather than an equal lambda created
+    /// separately, and for other values means that they are equal
+    Identical
+    ; "identical?",
+
     /// ## Less Than
     ///
     /// `a b -> (a < b)`
     ///
-    /// Evaluates to a true boolean value if the integer on top of the stack is greater than the
+    /// Evaluates to a true boolean value if the item on top of the stack is greater than the
     /// one below it.
     LessThan
     ; "<",
@@ -180,6 +525,15 @@ combinators! {
     Apply
     ; "apply",
 
+    /// ## Bi
+    ///
+    /// `a |f| |g| -> ... ...`
+    ///
+    /// Applies the function `|f|` (second from top of the stack) to the value `a` below it, and
+    /// then applies the function `|g|` (top of the stack) to `a`
+    Bi
+    ; "bi",
+
     /// ## Branch
     ///
     /// `a |f| |g| b -> a ...`
@@ -190,6 +544,25 @@ combinators! {
     Branch
     ; "?",
 
+    /// ## Cond
+    ///
+    /// `a [[|p| |f|]] |g| -> a ...`
+    ///
+    /// Tests the predicate `|p|` of each pair in a list (second from top of the stack) in order on
+    /// a copy of the value `a` below it, and applies the action `|f|` of the first pair whose
+    /// predicate holds to `a`, or applies the default function `|g|` (top of the stack) to `a` if
+    /// none of them hold
+    Cond
+    ; "cond",
+
+    /// ## Cleave
+    ///
+    /// `a [|f|] -> ...`
+    ///
+    /// Applies each function in a list (top of the stack) in order to the value `a` below it
+    Cleave
+    ; "cleave",
+
     /// ## Compose
     ///
     /// `|f| |g| -> |f g|`
@@ -198,7 +571,16 @@ combinators! {
     Compose
     ; "compose",
 
-    /// ## Under
+    /// ## Curry
+    ///
+    /// `a |f| -> |a f|`
+    ///
+    /// Creates a closure from the function on top of the stack that captures the value below it,
+    /// and pushes that value to the stack whenever the closure is applied
+    Curry
+    ; "curry",
+
+    /// ## Deep
     ///
     /// `a ... |f| # -> a ...`
     ///
@@ -207,6 +589,52 @@ combinators! {
     Deep
     ; "deep",
 
+    /// ## Dip
+    ///
+    /// `a b |f| -> ... b`
+    ///
+    /// Applies the function `|f|` (top of the stack) to the stack below the value `b` under it,
+    /// and then pushes `b` back to the stack
+    Dip
+    ; "dip",
+
+    /// ## Keep
+    ///
+    /// `a |f| -> ... a`
+    ///
+    /// Applies the function `|f|` (top of the stack) to the value `a` below it, and then pushes
+    /// `a` back to the stack
+    Keep
+    ; "keep",
+
+    /// ## Match
+    ///
+    /// `u |f| ... -> a ...`
+    ///
+    /// Dispatches on the variant of a union value `u` below one lambda for each variant of its
+    /// union, in the order the variants were declared, by pushing the fields of the value to the
+    /// stack and applying the lambda for its variant
+    Match
+    ; "match",
+
+    /// ## Spread
+    ///
+    /// `a ... [|f|] -> ...`
+    ///
+    /// Applies each function in a list (top of the stack) to the value at the same position among
+    /// as many values below it, so that the first function is applied to the deepest value
+    Spread
+    ; "spread",
+
+    /// ## Tri
+    ///
+    /// `a |f| |g| |h| -> ... ... ...`
+    ///
+    /// Applies the functions `|f|`, `|g|` and `|h|` (top of the stack) in order to the value `a`
+    /// below them
+    Tri
+    ; "tri",
+
     /// ## Under
     ///
     /// `a b |f| -> a ... b`
@@ -215,6 +643,94 @@ combinators! {
     Under
     ; "under",
 
+    /// # Continuation Combinators
+
+    /// ## Reset
+    ///
+    /// `|f| -> ...`
+    ///
+    /// Applies the function `|f|` (top of the stack), delimiting the continuations captured by
+    /// `shift` inside it
+    Reset
+    ; "reset",
+
+    /// ## Resume
+    ///
+    /// `k -> ...`
+    ///
+    /// Resumes the continuation `k` (top of the stack), restoring the data it captured to the top of
+    /// the stack
+    Resume
+    ; "resume",
+
+    /// ## Shift
+    ///
+    /// `... |f| -> k`
+    ///
+    /// Captures the rest of the computation up to the nearest `reset`, along with the data added
+    /// to the stack since the `reset`, as a continuation `k`, and applies the function `|f|` (top of
+    /// the stack) to it in place of the captured computation
+    Shift
+    ; "shift",
+
+    /// # Error Handling Combinators
+
+    /// ## Throw
+    ///
+    /// `a -> `
+    ///
+    /// Raises the value on top of the stack as an error
+    Throw
+    ; "throw",
+
+    /// ## Try
+    ///
+    /// `|f| |h| -> ...`
+    ///
+    /// Applies the function `|f|` (second from top of the stack), and if it raises an error,
+    /// restores the stack to its state before `|f|` was applied and applies the handler `|h|`
+    /// (top of the stack) to the error
+    Try
+    ; "try",
+
+    /// # Iteration Combinators
+
+    /// ## Each
+    ///
+    /// `[x] |f| -> ...`
+    ///
+    /// Applies the function `|f|` (top of the stack) to every item in the list `[x]` (second from
+    /// top of the stack) in order, without collecting the results into a list
+    Each
+    ; "each",
+
+    /// ## Times
+    ///
+    /// `# |f| -> ...`
+    ///
+    /// Applies the function `|f|` (top of the stack) the number of times given by the integer
+    /// below it
+    Times
+    ; "times",
+
+    /// ## Until
+    ///
+    /// `|c| |f| -> ...`
+    ///
+    /// Repeatedly applies the condition function `|c|` (second from top of the stack), followed by
+    /// the body function `|f|` (top of the stack) until the condition evaluates to true
+    Until
+    ; "until",
+
+    /// ## While
+    ///
+    /// `|c| |f| -> ...`
+    ///
+    /// Repeatedly applies the condition function `|c|` (second from top of the stack), followed by
+    /// the body function `|f|` (top of the stack) while the condition evaluates to true
+    While
+    ; "while",
+
     /// # List Processing Combinators
 
     /// ## Construct
@@ -230,10 +746,46 @@ combinators! {
     ///
     /// `[x] -> a`
     ///
-    /// Turns a list on top of the stack into its size
+    /// Turns a list or bytes on top of the stack into its size
     Length
     ; "length",
 
+    /// ## All
+    ///
+    /// `[x] |f| -> b`
+    ///
+    /// Turns the list `[x]` (second from top of the stack) into whether a predicate function `|f|`
+    /// (top of the stack) holds for all of its items
+    All
+    ; "all",
+
+    /// ## Any
+    ///
+    /// `[x] |f| -> b`
+    ///
+    /// Turns the list `[x]` (second from top of the stack) into whether a predicate function `|f|`
+    /// (top of the stack) holds for any of its items
+    Any
+    ; "any",
+
+    /// ## Count
+    ///
+    /// `[x] |f| -> #`
+    ///
+    /// Turns the list `[x]` (second from top of the stack) into the number of its items that a
+    /// predicate function `|f|` (top of the stack) holds for
+    Count
+    ; "count",
+
+    /// ## Enumerate
+    ///
+    /// `[x] -> [[# x]]`
+    ///
+    /// Turns the list on top of the stack into a list of two-item lists, each made of the index of
+    /// an item and the item
+    Enumerate
+    ; "enumerate",
+
     /// ## Filter
     ///
     /// `[x] |f| -> [y]`
@@ -243,6 +795,23 @@ combinators! {
     Filter
     ; "filter",
 
+    /// ## First
+    ///
+    /// `[x] -> a`
+    ///
+    /// Turns a non-empty list on top of the stack into its first item
+    First
+    ; "first",
+
+    /// ## Flatten
+    ///
+    /// `[[x]] -> [x]`
+    ///
+    /// Replaces each list inside the list on top of the stack with its items, keeping items that
+    /// are not lists as they are
+    Flatten
+    ; "flatten",
+
     /// ## Fold
     ///
     /// `[x] a |f| -> a`
@@ -257,7 +826,8 @@ combinators! {
     ///
     /// `# [x] -> a`
     ///
-    /// Returns the value at a given index in a list
+    /// Returns the value at a given index in a list, or the byte at that index in bytes as an
+    /// integer
     Index
     ; "index",
 
@@ -265,10 +835,27 @@ combinators! {
     ///
     /// `[x] [y] -> [x y]`
     ///
-    /// Joins the two lists on top of the stack into one
+    /// Joins the two lists or two bytes on top of the stack into one
     Join
     ; "join",
 
+    /// ## Iota
+    ///
+    /// `# -> [#]`
+    ///
+    /// Turns an integer `n` on top of the stack into a list of the integers from 0 up to but not
+    /// including `n`
+    Iota
+    ; "iota",
+
+    /// ## Last
+    ///
+    /// `[x] -> a`
+    ///
+    /// Turns a non-empty list on top of the stack into its last item
+    Last
+    ; "last",
+
     /// ## MaybeAppend
     ///
     /// `... a b -> ... b OR ...`
@@ -277,6 +864,14 @@ combinators! {
     MaybeAppend
     ; ":",
 
+    /// ## MaybeIncrement
+    ///
+    /// `# ? -> #`
+    ///
+    /// Increments a count below a boolean if the boolean is true (internal use only)
+    MaybeIncrement
+    ; "+?",
+
     /// ## Map
     ///
     /// `[x] |f| -> [y]`
@@ -286,247 +881,1302 @@ combinators! {
     Map
     ; "map",
 
-    /// # Stack Manipulation Combinators
+    /// ## Maximum
+    ///
+    /// `[x] -> a`
+    ///
+    /// Turns a non-empty list on top of the stack into its greatest item
+    Maximum
+    ; "max",
 
-    /// ## Copy
+    /// ## Minimum
     ///
-    /// `a -> a a`
+    /// `[x] -> a`
     ///
-    /// Duplicates the item on top of the stack
-    Copy
-    ; "copy",
+    /// Turns a non-empty list on top of the stack into its least item
+    Minimum
+    ; "min",
 
-    /// ## Drop
+    /// ## Pack
     ///
-    /// `a b -> a`
+    /// `... # -> [x]`
     ///
-    /// Removes the item on top of the stack
-    Drop
-    ; "drop",
+    /// Collects a number (top of the stack) of the items below it into a list, keeping them in the
+    /// order they were on the stack
+    Pack
+    ; "pack",
 
-    /// ## Hop
+    /// ## Product
     ///
-    /// `a b -> a b a`
+    /// `[#] -> #`
     ///
-    /// Pushes a duplicate of the second value from top of the stack
-    Hop
-    ; "hop",
+    /// Turns a list of numbers on top of the stack into their product, which is 1 for an empty
+    /// list
+    Product
+    ; "product",
 
-    /// ## Pick
+    /// ## Range
     ///
-    /// `c ... # -> ... c`
+    /// `# # -> [#]`
     ///
-    /// Consumes an index from the top of the stack, and then pushes a duplicate of the item at that
-    /// index
-    Pick
-    ; "pick",
+    /// Turns a start integer (second from top of the stack) and an end integer (top of the stack)
+    /// into a list of the integers from the start up to but not including the end, or into a
+    /// sequence that counts from the start without end if the end is the float `inf`
+    Range
+    ; "range",
 
-    /// ## Rotate
+    /// ## Range Step
     ///
-    /// `a b c -> c a b`
+    /// `# # # -> [#]`
     ///
-    /// Moves the top item on the stack to the position below the next top two items
-    Rotate
-    ; "rotate",
+    /// Turns a start integer (third from top of the stack) and an end integer (second from top of
+    /// the stack) into a list of the integers from the start counting by a step (top of the
+    /// stack) for as long as they are before the end, counting down if the step is negative, or
+    /// into a sequence that counts without end if the end is an infinite float in the direction of
+    /// the step
+    RangeStep
+    ; "range-step",
+
+    /// ## Repeat
+    ///
+    /// `a # -> [a]`
+    ///
+    /// Turns a value (second from top of the stack) into a list holding it a number of times (top
+    /// of the stack)
+    Repeat
+    ; "repeat",
 
-    /// ## Swap
+    /// ## Rest
     ///
-    /// `a b -> b a`
+    /// `[x] -> [y]`
     ///
-    /// Swaps the two items on top of the stack
-    Swap
-    ; "swap",
+    /// Removes the first item from a non-empty list on top of the stack
+    Rest
+    ; "rest",
 
-}
-impl Combinator {
+    /// ## Reverse
+    ///
+    /// `[x] -> [y]`
+    ///
+    /// Reverses the order of the items in the list on top of the stack
+    Reverse
+    ; "reverse",
 
-    /// Evaluates this `Combinator` on a `VirtualMachine`
-    pub fn evaluate<'a>(
-        &self,
-        stack: &mut DataStack,
-        environment: &'a Environment
-    ) -> ControlAction<'a> {
-        use Combinator::*;
-        match self {
+    /// ## Slice
+    ///
+    /// `[x] # # -> [y]`
+    ///
+    /// Turns a list or bytes (third from top of the stack) into its items from the start index
+    /// (second from top of the stack) up to but not including the end index (top of the stack),
+    /// where negative indices count from the end and indices beyond either end are clamped
+    Slice
+    ; "slice",
 
-            // arithmetic combinators
+    /// ## Sort
+    ///
+    /// `[x] -> [y]`
+    ///
+    /// Sorts the list on top of the stack in ascending order, keeping equal items in the order
+    /// they were in
+    Sort
+    ; "sort",
 
-            Add => arithmetic_operation(stack, |a, b| a + b),
+    /// ## Sort By
+    ///
+    /// `[x] |f| -> [y]`
+    ///
+    /// Sorts the list `[x]` (second from top of the stack) in ascending order of the keys made by
+    /// applying the function `|f|` (top of the stack) to each item, keeping items with equal keys
+    /// in the order they were in
+    SortBy
+    ; "sort-by",
 
-            Divide => arithmetic_operation(stack, |a, b| a / b),
+    /// ## Split At
+    ///
+    /// `[x] # -> [y] [z]`
+    ///
+    /// Splits the list `[x]` (second from top of the stack) into the items before an index (top
+    /// of the stack) and the items from that index on, where a negative index counts from the end
+    /// and an index beyond either end is clamped
+    SplitAt
+    ; "split-at",
 
-            Remainder => arithmetic_operation(stack, |a, b| a % b),
+    /// ## Sum
+    ///
+    /// `[#] -> #`
+    ///
+    /// Turns a list of numbers on top of the stack into their sum, which is 0 for an empty list
+    Sum
+    ; "sum",
 
-            Multiply => arithmetic_operation(stack, |a, b| a * b),
+    /// ## Uncons
+    ///
+    /// `[x] -> a [y]`
+    ///
+    /// Splits a non-empty list on top of the stack into its first item and a list of the rest of
+    /// its items
+    Uncons
+    ; "uncons",
 
-            Subtract => arithmetic_operation(stack, |a, b| a - b),
+    /// ## Unique
+    ///
+    /// `[x] -> [y]`
+    ///
+    /// Removes every item from the list on top of the stack that is equal to an item before it
+    Unique
+    ; "unique",
 
-            // boolean combinators
+    /// ## Unpack
+    ///
+    /// `[x] -> ...`
+    ///
+    /// Pushes every item of the list on top of the stack to the stack in order, so that its last
+    /// item is on top
+    Unpack
+    ; "unpack",
 
-            And => boolean_logic_operation(stack, |a, b| a && b),
+    /// ## Zip
+    ///
+    /// `[x] [y] -> [[x y]]`
+    ///
+    /// Pairs the items of two lists on top of the stack at the same indices into two-item lists,
+    /// stopping at the end of the shorter list
+    Zip
+    ; "zip",
 
-            ExclusiveOr => boolean_logic_operation(stack, |a, b| a ^ b),
+    /// # Byte Combinators
 
-            Not => if let Some(top) = stack.pop() {
-                if let Value::Boolean(boolean) = top {
-                    stack.push(Value::Boolean(!boolean));
-                    Continue
-                } else {
-                    Error ("Can only perform boolean \"not\" operation on boolean data".to_string())
-                }
-            } else {
-                Error ("Cannot perform boolean \"not\" operation on empty stack".to_string())
-            },
+    /// ## Base64 To Bytes
+    ///
+    /// `b -> b`
+    ///
+    /// Decodes bytes on top of the stack holding padded standard base64 text into the bytes it
+    /// encodes
+    Base64ToBytes
+    ; "base64>bytes",
 
-            Or => boolean_logic_operation(stack, |a, b| a || b),
+    /// ## Bytes To Base64
+    ///
+    /// `b -> b`
+    ///
+    /// Encodes bytes on top of the stack as padded standard base64 text
+    BytesToBase64
+    ; "bytes>base64",
 
-            // comparison combinators
+    /// ## Bytes To Hexadecimal
+    ///
+    /// `b -> b`
+    ///
+    /// Encodes bytes on top of the stack as lowercase hexadecimal text
+    BytesToHexadecimal
+    ; "bytes>hex",
 
-            Equality => comparison_operation(stack, |a, b| Ok(a == b)),
+    /// ## Bytes To List
+    ///
+    /// `b -> [#]`
+    ///
+    /// Turns bytes on top of the stack into a list of integers from 0 to 255
+    BytesToList
+    ; "bytes>list",
 
-            GreaterThan => comparison_operation(stack, |a, b| match (a, b) {
-                (Value::Integer(a), Value::Integer(b)) => Ok(a > b),
-                _ => Err("Can only perform \"greater than\" operation on integers")
-            }),
+    /// ## Decode Big-Endian
+    ///
+    /// `b -> #`
+    ///
+    /// Turns bytes on top of the stack into the non-negative integer they encode with the most
+    /// significant byte first
+    DecodeBigEndian
+    ; "decode-be",
 
-            LessThan => comparison_operation(stack, |a, b| match (a, b) {
-                (Value::Integer(a), Value::Integer(b)) => Ok(a < b),
-                _ => Err("Can only perform \"less than\" operation on integers")
-            }),
+    /// ## Decode Little-Endian
+    ///
+    /// `b -> #`
+    ///
+    /// Turns bytes on top of the stack into the non-negative integer they encode with the least
+    /// significant byte first
+    DecodeLittleEndian
+    ; "decode-le",
 
-            // functional combinators
+    /// ## Encode Big-Endian
+    ///
+    /// `# # -> b`
+    ///
+    /// Encodes a non-negative integer (second from top of the stack) as bytes of the width on top
+    /// of the stack, with the most significant byte first
+    EncodeBigEndian
+    ; "encode-be",
 
-            Apply => {
-                let top: Option<Value> = stack.pop();
-                if let Some (Value::Lambda (reference)) = top {
-                    let lambda: Function = reference.get(environment);
-                    Push (lambda)
-                } else { Error ("Stack must have a lambda on top to be applied".to_string()) }
+    /// ## Encode Little-Endian
+    ///
+    /// `# # -> b`
+    ///
+    /// Encodes a non-negative integer (second from top of the stack) as bytes of the width on top
+    /// of the stack, with the least significant byte first
+    EncodeLittleEndian
+    ; "encode-le",
+
+    /// ## Hexadecimal To Bytes
+    ///
+    /// `b -> b`
+    ///
+    /// Decodes bytes on top of the stack holding hexadecimal text in either case into the bytes it
+    /// encodes
+    HexadecimalToBytes
+    ; "hex>bytes",
+
+    /// ## List To Bytes
+    ///
+    /// `[#] -> b`
+    ///
+    /// Turns a list of integers from 0 to 255 on top of the stack into bytes
+    ListToBytes
+    ; "list>bytes",
+
+    /// # Map Combinators
+
+    /// ## Entries
+    ///
+    /// `{m} -> [[k v]]`
+    ///
+    /// Turns the map on top of the stack into a list of its entries as two-item lists, ordered
+    /// by key
+    Entries
+    ; "entries",
+
+    /// ## Get
+    ///
+    /// `{m} k -> v`
+    ///
+    /// Returns the value stored under the key `k` (top of the stack) in the map `{m}` (second from
+    /// top of the stack)
+    Get
+    ; "get",
+
+    /// ## Has
+    ///
+    /// `{m} k -> bool`
+    ///
+    /// Checks whether the map `{m}` (second from top of the stack) has a value stored under the key
+    /// `k` (top of the stack)
+    Has
+    ; "has?",
+
+    /// ## Keys
+    ///
+    /// `{m} -> [k]`
+    ///
+    /// Turns the map on top of the stack into a list of its keys in order
+    Keys
+    ; "keys",
+
+    /// ## Map Values
+    ///
+    /// `{m} |f| -> {n}`
+    ///
+    /// Applies the function `|f|` (top of the stack) to every value in the map `{m}` (second from
+    /// top of the stack) creating a new map with the same keys
+    MapValues
+    ; "map-values",
+
+    /// ## Put
+    ///
+    /// `{m} k v -> {n}`
+    ///
+    /// Stores the value `v` (top of the stack) under the key `k` (second from top of the stack) in
+    /// the map `{m}` (third from top of the stack), replacing any value already stored under it
+    Put
+    ; "put",
+
+    /// ## Remove
+    ///
+    /// `{m} k -> {n}`
+    ///
+    /// Removes the key `k` (top of the stack) and its value from the map `{m}` (second from top of
+    /// the stack)
+    Remove
+    ; "remove",
+
+    /// ## Values
+    ///
+    /// `{m} -> [v]`
+    ///
+    /// Turns the map on top of the stack into a list of its values, ordered by key
+    Values
+    ; "values",
+
+    /// # Lazy Sequence Combinators
+
+    /// ## Drop While
+    ///
+    /// `[x] |f| -> [y]`
+    ///
+    /// Lazily skips the items at the start of the sequence or list `[x]` (second from top of the
+    /// stack) for as long as the predicate function `|f|` (top of the stack) holds for them
+    DropWhile
+    ; "drop-while",
+
+    /// ## Force
+    ///
+    /// `[x] -> [y]`
+    ///
+    /// Pulls every item from the sequence `[x]` on top of the stack, collecting them into a list
+    Force
+    ; "force",
+
+    /// ## Iterate
+    ///
+    /// `a |f| -> [x]`
+    ///
+    /// Creates an infinite sequence starting with the seed `a` (second from top of the stack),
+    /// where each following item is made by applying the step function `|f|` (top of the stack)
+    /// to the previous one
+    Iterate
+    ; "iterate",
+
+    /// ## Lazy Filter
+    ///
+    /// `[x] |f| -> [y]`
+    ///
+    /// Lazily filters the sequence or list `[x]` (second from top of the stack), keeping only the
+    /// items that match a predicate function `|f|` (top of the stack)
+    LazyFilter
+    ; "lazy-filter",
+
+    /// ## Lazy Map
+    ///
+    /// `[x] |f| -> [y]`
+    ///
+    /// Lazily applies the function `|f|` (top of the stack) to every item in the sequence or list
+    /// `[x]` (second from top of the stack)
+    LazyMap
+    ; "lazy-map",
+
+    /// ## Next
+    ///
+    /// `[x] -> a [y] true OR false`
+    ///
+    /// Pulls the next item `a` from the sequence or list `[x]` on top of the stack, leaving the
+    /// rest of the sequence `[y]` and `true` above it, or leaving `false` if it has no more items
+    Next
+    ; "next",
+
+    /// ## Take
+    ///
+    /// `[x] # -> [y]`
+    ///
+    /// Lazily keeps only the given number of items from the start of the sequence or list `[x]`
+    /// (second from top of the stack)
+    Take
+    ; "take",
+
+    /// ## Take While
+    ///
+    /// `[x] |f| -> [y]`
+    ///
+    /// Lazily keeps the items at the start of the sequence or list `[x]` (second from top of the
+    /// stack) for as long as the predicate function `|f|` (top of the stack) holds for them
+    TakeWhile
+    ; "take-while",
+
+    /// # Stack Manipulation Combinators
+
+    /// ## Copy
+    ///
+    /// `a -> a a`
+    ///
+    /// Duplicates the item on top of the stack
+    Copy
+    ; "copy",
+
+    /// ## Drop
+    ///
+    /// `a b -> a`
+    ///
+    /// Removes the item on top of the stack
+    Drop
+    ; "drop",
+
+    /// ## Hop
+    ///
+    /// `a b -> a b a`
+    ///
+    /// Pushes a duplicate of the second value from top of the stack
+    Hop
+    ; "hop",
+
+    /// ## Pick
+    ///
+    /// `c ... # -> ... c`
+    ///
+    /// Consumes an index from the top of the stack, and then pushes a duplicate of the item at that
+    /// index
+    Pick
+    ; "pick",
+
+    /// ## Rotate
+    ///
+    /// `a b c -> c a b`
+    ///
+    /// Moves the top item on the stack to the position below the next top two items
+    Rotate
+    ; "rotate",
+
+    /// ## Swap
+    ///
+    /// `a b -> b a`
+    ///
+    /// Swaps the two items on top of the stack
+    Swap
+    ; "swap",
+
+}
+impl Combinator {
+
+    /// Evaluates this `Combinator` on a `VirtualMachine`
+    pub fn evaluate<'a>(
+        &self,
+        stack: &mut DataStack,
+        environment: &'a Environment
+    ) -> ControlAction<'a> {
+        use Combinator::*;
+        match self {
+
+            // arithmetic combinators
+
+            Add => arithmetic_operation(
+                stack,
+                |a, b| Value::Integer (a + b),
+                |a, b| a + b,
+                |a, b| a + b
+            ),
+
+            Divide => if divisor_is_zero(stack) {
+                Error ("Cannot perform `/` with a divisor of zero".to_string())
+            } else {
+                arithmetic_operation(
+                    stack,
+                    |a, b| Value::from_rational(Rational::from_fraction(a, b)
+                        .expect("Divisor has already been checked to not be zero")),
+                    |a, b| a / b,
+                    |a, b| a / b
+                )
             },
 
-            Compose => match (stack.pop(), stack.pop()) {
-                (Some (Value::Lambda (b_reference)), Some (Value::Lambda (a_reference))) => {
-                    stack.push(Value::Lambda (a_reference.compose(b_reference)));
+            Remainder => if divisor_is_zero(stack) {
+                Error ("Cannot perform `%` with a divisor of zero".to_string())
+            } else {
+                arithmetic_operation(
+                    stack,
+                    |a, b| Value::Integer (a % b),
+                    |a, b| a % b,
+                    |a, b| a % b
+                )
+            },
+
+            Multiply => arithmetic_operation(
+                stack,
+                |a, b| Value::Integer (a * b),
+                |a, b| a * b,
+                |a, b| a * b
+            ),
+
+            Subtract => arithmetic_operation(
+                stack,
+                |a, b| Value::Integer (a - b),
+                |a, b| a - b,
+                |a, b| a - b
+            ),
+
+            // integer combinators
+
+            Absolute => match stack.pop() {
+                Some (Value::Float (float)) => {
+                    stack.push(Value::Float (Float::new(float.value().abs())));
                     Continue
                 }
-                _ => Error ("Cannot perform `compose` operation unless there are two lambdas on \
-                top of the stack".to_string()),
+                Some (Value::Integer (integer)) => {
+                    stack.push(Value::Integer (integer.abs()));
+                    Continue
+                }
+                Some (Value::Rational (rational)) => {
+                    stack.push(Value::Rational (rational.abs()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `abs` unless there is a number on top of the stack"
+                    .to_string()),
             }
 
-            Branch => match (stack.pop(), stack.pop()) {
-                (Some(Value::Lambda (false_reference)), Some(Value::Lambda(true_reference))) =>
-                    match stack.pop() {
-                        Some(Value::Boolean (boolean)) => if boolean {
-                            let true_lambda: Function = true_reference.get(environment);
-                            Push (true_lambda)
-                        } else {
-                            let false_lambda: Function = false_reference.get(environment);
-                            Push (false_lambda)
-                        }
-                        _ => Error ("Cannot perform `?` operation unless there is a boolean below \
-                        the two lambdas on top of the stack".to_string())
-                    },
-                _ => Error ("Cannot perform `?` operation unless there are two lambdas on top of \
-                the stack".to_string()),
-            }
+            BitAnd => integer_operation(stack, |a, b| Ok (a & b), "bit-and"),
 
-            Deep => match (stack.pop(), stack.pop()) {
-                (Some (Value::Integer (integer)), Some (Value::Lambda (reference))) => {
-                    if let Some (data) = stack.pop_slice(integer.as_wrapping_index(stack.size())) {
-                        let lambda: Function = reference.get(environment)
-                            .extended(data.into_iter().map(|value| Term::Data(value)));
-                        Push (lambda)
-                    } else { Error ("Cannot perform `deep` operation unless there is an index on \
-                    top of the stack, and a lambda below it".to_string()) }
-                }
-                _ => Error ("Cannot perform `deep` operation unless there is an index on \
-                    top of the stack, and a lambda below it".to_string())
-            }
+            BitLength => unary_integer_operation(stack, |a| Ok (a.bit_length()), "bit-length"),
 
-            Under => match (stack.pop(), stack.pop()) {
-                (Some(Value::Lambda (reference)), Some(top)) => {
-                    let lambda: Function = reference.get(environment)
-                        .extended([Term::Data (top)].into_iter());
-                    Push (lambda)
-                }
-                _ => Error ("Cannot perform `under` operation unless there is a lambda under \
-                another item on top of the stack".to_string()),
-            }
+            BitNot => unary_integer_operation(stack, |a| Ok (!a), "bit-not"),
 
-            // list combinators
+            BitOr => integer_operation(stack, |a, b| Ok (a | b), "bit-or"),
 
-            Append => match (stack.pop(), stack.pop()) {
-                (Some (value), Some (Value::List (mut list_items))) => {
-                    list_items.push(value);
-                    stack.push(Value::List (list_items));
+            BitXor => integer_operation(stack, |a, b| Ok (a ^ b), "bit-xor"),
+
+            DivideFloored => integer_operation(stack, |a, b| if b.is_zero() {
+                Err ("Cannot perform `div` with a divisor of zero")
+            } else { Ok (a.div_floor(&b)) }, "div"),
+
+            DivideModulo => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (b)), Some (Value::Integer (a))) => if b.is_zero() {
+                    Error ("Cannot perform `divmod` with a divisor of zero".to_string())
+                } else {
+                    stack.push(Value::Integer (a.div_floor(&b)));
+                    stack.push(Value::Integer (a.mod_floor(&b)));
                     Continue
-                }
-                _ => Error ("Cannot perform `append` unless there is a list below the value to \
-                be appended to it on the stack".to_string()),
+                },
+                _ => Error ("Cannot perform `divmod` unless there are two integers on top of the \
+                stack".to_string()),
             }
 
-            Length => match stack.pop() {
-                Some (Value::List (items)) => {
-                    stack.push(Value::Integer (Integer::from_usize(items.len())));
+            GreatestCommonDivisor => integer_operation(stack, |a, b| Ok (a.gcd(&b)), "gcd"),
+
+            IntegerSquareRoot => unary_integer_operation(stack, |a| a.isqrt()
+                .ok_or("Cannot perform `isqrt` on a negative integer"), "isqrt"),
+
+            LeastCommonMultiple => integer_operation(stack, |a, b| Ok (a.lcm(&b)), "lcm"),
+
+            Modulo => integer_operation(stack, |a, b| if b.is_zero() {
+                Err ("Cannot perform `mod` with a divisor of zero")
+            } else { Ok (a.mod_floor(&b)) }, "mod"),
+
+            Negate => match stack.pop() {
+                Some (Value::Float (float)) => {
+                    stack.push(Value::Float (Float::new(-float.value())));
+                    Continue
+                }
+                Some (Value::Integer (integer)) => {
+                    stack.push(Value::Integer (-integer));
                     Continue
                 }
-                _ => Error ("Cannot perform `length` unless there is a list on top of the stack"
+                Some (Value::Rational (rational)) => {
+                    stack.push(Value::Rational (-rational));
+                    Continue
+                }
+                _ => Error ("Cannot perform `negate` unless there is a number on top of the stack"
                     .to_string()),
-            },
+            }
 
-            Filter => match (stack.pop(), stack.pop()) {
-                (Some (Value::Lambda (reference)), Some (Value::List (list))) => {
-                    let mut function: Function = Function::Composed(Vec::with_capacity(list.len()));
-                    stack.push(Value::List(Vec::with_capacity(list.len())));
-                    for value in list {
-                        function = function.extended([
-                            Term::Data (value.clone()),
-                            Term::Data (Value::Lambda (reference.clone())),
-                            Term::Combinator (Apply),
-                            Term::Data (value),
-                            Term::Combinator (MaybeAppend),
-                        ].into_iter())
+            PowerModulo => match (stack.pop(), stack.pop(), stack.pop()) {
+                (
+                    Some (Value::Integer (modulus)),
+                    Some (Value::Integer (exponent)),
+                    Some (Value::Integer (base)),
+                ) => match base.modpow(&exponent, &modulus) {
+                    Some (power) => {
+                        stack.push(Value::Integer (power));
+                        Continue
                     }
-                    Push (function)
+                    None => Error ("Cannot perform `powmod` with a negative exponent or a modulus \
+                    of zero".to_string()),
+                },
+                _ => Error ("Cannot perform `powmod` unless there are three integers on top of the \
+                stack".to_string()),
+            }
+
+            Quotient => integer_operation(stack, |a, b| if b.is_zero() {
+                Err ("Cannot perform `quot` with a divisor of zero")
+            } else { Ok (a / b) }, "quot"),
+
+            ShiftLeft => integer_operation(stack, |a, b| a.shifted_left(&b)
+                .ok_or("Cannot perform `shift-left` with a negative shift, or with a shift that \
+                makes the result too large"), "shift-left"),
+
+            ShiftRight => integer_operation(stack, |a, b| a.shifted_right(&b)
+                .ok_or("Cannot perform `shift-right` with a negative shift"), "shift-right"),
+
+            Sign => match stack.pop() {
+                Some (Value::Integer (integer)) => {
+                    stack.push(Value::Integer (integer.signum()));
+                    Continue
                 }
-                _ => Error ("Cannot perform `filter` unless there is a lambda above a list on top \
+                Some (Value::Rational (rational)) => {
+                    stack.push(Value::Integer (rational.signum()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `sign` unless there is an integer or rational on top \
                 of the stack".to_string()),
             }
 
-            Fold => match (stack.pop(), stack.pop(), stack.pop()) {
+            // rational combinators
+
+            Ceiling => rounding_operation(stack, Rational::ceil, "ceil"),
+
+            Denominator => rounding_operation(stack, Rational::denominator, "denominator"),
+
+            Floor => rounding_operation(stack, Rational::floor, "floor"),
+
+            Numerator => rounding_operation(stack, Rational::numerator, "numerator"),
+
+            Round => rounding_operation(stack, Rational::round, "round"),
+
+            // floating-point combinators
+
+            ArcCosine => float_operation(stack, f64::acos, "acos"),
+
+            ArcSine => float_operation(stack, f64::asin, "asin"),
+
+            ArcTangent => float_operation(stack, f64::atan, "atan"),
+
+            Cosine => float_operation(stack, f64::cos, "cos"),
+
+            Exponential => float_operation(stack, f64::exp, "exp"),
+
+            FloatToInteger => match stack.pop() {
+                Some (Value::Float (float)) => match Integer::from_float(float) {
+                    Some (integer) => {
+                        stack.push(Value::Integer (integer));
+                        Continue
+                    }
+                    None => Error ("Cannot perform `float>int` on an infinite or NaN float"
+                        .to_string()),
+                },
+                _ => Error ("Cannot perform `float>int` unless there is a float on top of the stack"
+                    .to_string()),
+            }
+
+            IntegerToFloat => match stack.pop() {
+                Some (Value::Integer (integer)) => {
+                    stack.push(Value::Float (integer.to_float()));
+                    Continue
+                }
+                Some (Value::Rational (rational)) => {
+                    stack.push(Value::Float (rational.to_float()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `int>float` unless there is an integer or rational on \
+                top of the stack".to_string()),
+            }
+
+            Logarithm => float_operation(stack, f64::ln, "log"),
+
+            Power => match (stack.pop(), stack.pop()) {
+                (Some (Value::Float (exponent)), Some (Value::Float (base))) => {
+                    stack.push(Value::Float (Float::new(base.value().powf(exponent.value()))));
+                    Continue
+                }
+                (Some (Value::Integer (exponent)), Some (Value::Integer (base)))
+                if !exponent.is_negative() => match base.pow(&exponent) {
+                    Some (power) => {
+                        stack.push(Value::Integer (power));
+                        Continue
+                    }
+                    None => Error ("Cannot perform `pow` because the result would be too large"
+                        .to_string()),
+                },
+                (Some (Value::Integer (exponent)), Some (base @ (Value::Integer (_)
+                | Value::Rational (_)))) => {
+                    let base: Rational = match base {
+                        Value::Integer (integer) if integer.is_zero() => return Error ("Cannot \
+                        perform `pow` to raise zero to a negative power".to_string()),
+                        Value::Integer (integer) => Rational::from_integer(integer),
+                        Value::Rational (rational) => rational,
+                        _ => unreachable!("Base is an integer or a rational"),
+                    };
+                    match base.pow(&exponent) {
+                        Some (power) => {
+                            stack.push(Value::from_rational(power));
+                            Continue
+                        }
+                        None => Error ("Cannot perform `pow` because the result would be too \
+                        large".to_string()),
+                    }
+                }
+                _ => Error ("Cannot perform `pow` unless there is an integer exponent above an \
+                integer or rational base, or two floats on top of the stack".to_string()),
+            }
+
+            Sine => float_operation(stack, f64::sin, "sin"),
+
+            SquareRoot => float_operation(stack, f64::sqrt, "sqrt"),
+
+            Tangent => float_operation(stack, f64::tan, "tan"),
+
+            // boolean combinators
+
+            And => boolean_logic_operation(stack, |a, b| a && b),
+
+            ExclusiveOr => boolean_logic_operation(stack, |a, b| a ^ b),
+
+            Not => if let Some(top) = stack.pop() {
+                if let Value::Boolean(boolean) = top {
+                    stack.push(Value::Boolean(!boolean));
+                    Continue
+                } else {
+                    Error ("Can only perform boolean \"not\" operation on boolean data".to_string())
+                }
+            } else {
+                Error ("Cannot perform boolean \"not\" operation on empty stack".to_string())
+            },
+
+            Or => boolean_logic_operation(stack, |a, b| a || b),
+
+            // comparison combinators
+
+            Equality => comparison_operation(stack, |a, b| Ok(a == b)),
+
+            Identical => comparison_operation(stack, |a, b| Ok(a.is_identical(&b))),
+
+            Compare => match (stack.pop(), stack.pop()) {
+                (Some (b), Some (a)) => {
+                    stack.push(Value::Integer (Integer::from_isize(a.cmp(&b) as isize)));
+                    Continue
+                }
+                _ => Error ("Not enough items in the stack to perform comparison operation"
+                    .to_string()),
+            }
+
+            GreaterThan => comparison_operation(stack, |a, b| Ok(a > b)),
+
+            LessThan => comparison_operation(stack, |a, b| Ok(a < b)),
+
+            // functional combinators
+
+            Apply => {
+                let top: Option<Value> = stack.pop();
+                if let Some (Value::Lambda (reference)) = top {
+                    let lambda: Function = reference.get(environment);
+                    Push (lambda)
+                } else { Error ("Stack must have a lambda on top to be applied".to_string()) }
+            },
+
+            Compose => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (b_reference)), Some (Value::Lambda (a_reference))) => {
+                    stack.push(Value::Lambda (a_reference.compose(b_reference)));
+                    Continue
+                }
+                _ => Error ("Cannot perform `compose` operation unless there are two lambdas on \
+                top of the stack".to_string()),
+            }
+
+            Curry => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (value)) => {
+                    stack.push(Value::Lambda (reference.curry(value)));
+                    Continue
+                }
+                _ => Error ("Cannot perform `curry` operation unless there is a lambda above a \
+                value on top of the stack".to_string()),
+            }
+
+            Bi => match (stack.pop(), stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (g)), Some (Value::Lambda (f)), Some (value)) =>
+                    cleaved(value, vec![f, g]),
+                _ => Error ("Cannot perform `bi` unless there are two lambdas above a value on top \
+                of the stack".to_string()),
+            }
+
+            Branch => match (stack.pop(), stack.pop()) {
+                (Some(Value::Lambda (false_reference)), Some(Value::Lambda(true_reference))) =>
+                    match stack.pop() {
+                        Some(Value::Boolean (boolean)) => if boolean {
+                            let true_lambda: Function = true_reference.get(environment);
+                            Push (true_lambda)
+                        } else {
+                            let false_lambda: Function = false_reference.get(environment);
+                            Push (false_lambda)
+                        }
+                        _ => Error ("Cannot perform `?` operation unless there is a boolean below \
+                        the two lambdas on top of the stack".to_string())
+                    },
+                _ => Error ("Cannot perform `?` operation unless there are two lambdas on top of \
+                the stack".to_string()),
+            }
+
+            Cleave => match (stack.pop().and_then(lambdas), stack.pop()) {
+                (Some (functions), Some (value)) => cleaved(value, functions),
+                _ => Error ("Cannot perform `cleave` unless there is a list of lambdas above a \
+                value on top of the stack".to_string()),
+            }
+
+            Cond => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (default)), Some (Value::List (clauses))) => {
+                    let clauses: Option<Vec<(LambdaReference, LambdaReference)>> = clauses
+                        .into_iter()
+                        .map(|clause| match clause {
+                            Value::List (pair) => match <[Value; 2]>::try_from(pair) {
+                                Ok ([Value::Lambda (predicate), Value::Lambda (action)]) =>
+                                    Some ((predicate, action)),
+                                _ => None,
+                            },
+                            _ => None,
+                        })
+                        .collect();
+                    match clauses {
+                        Some (clauses) => PushFrame (ControlFrame::Cond {
+                            action: None,
+                            clauses: clauses.into_iter(),
+                            default: Some (default),
+                        }),
+                        None => Error ("Cannot perform `cond` unless every clause is a list of a \
+                        predicate lambda and an action lambda".to_string()),
+                    }
+                }
+                _ => Error ("Cannot perform `cond` unless there is a default lambda above a list \
+                of clauses on top of the stack".to_string()),
+            }
+
+            Deep => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (integer)), Some (Value::Lambda (reference))) => {
+                    if let Some (data) = stack.pop_slice(integer.as_wrapping_index(stack.size())) {
+                        let lambda: Function = reference.get(environment)
+                            .extended(data.into_iter().map(|value| Term::Data(value)));
+                        Push (lambda)
+                    } else { Error ("Cannot perform `deep` operation unless there is an index on \
+                    top of the stack, and a lambda below it".to_string()) }
+                }
+                _ => Error ("Cannot perform `deep` operation unless there is an index on \
+                    top of the stack, and a lambda below it".to_string())
+            }
+
+            Dip => dipped(stack, environment, "dip"),
+
+            Keep => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (value)) => {
+                    stack.push(value.clone());
+                    Push (reference.get(environment).extended([Term::Data (value)].into_iter()))
+                }
+                _ => Error ("Cannot perform `keep` unless there is a lambda above a value on top \
+                of the stack".to_string()),
+            }
+
+            Match => {
+                let cases: usize = (0..stack.size())
+                    .take_while(|index|
+                        matches!(stack.get_from_top(*index), Some (Value::Lambda (_))))
+                    .count();
+                let variant: usize = match stack.get_from_top(cases) {
+                    Some (Value::Record (record)) => match record.kind().union() {
+                        Some ((union, variant)) if union.variants().len() == cases => variant,
+                        Some ((union, _)) => return Error (format!("Cannot perform `match` on a \
+                        value of the union `{}` unless there is one lambda above it for each of \
+                        its variants: {}", union.name(), union.variants().join(", "))),
+                        None => return Error ("Cannot perform `match` on a record that is not a \
+                        variant of a union".to_string()),
+                    },
+                    _ => return Error ("Cannot perform `match` unless there is a union value below \
+                    a lambda for each of its variants".to_string()),
+                };
+                let Some (Value::Lambda (reference)) = stack.pop_slice(cases)
+                    .map(|mut lambdas| lambdas.swap_remove(variant))
+                else { unreachable!("The lambdas have already been counted") };
+                let Some (Value::Record (record)) = stack.pop()
+                else { unreachable!("The union value has already been found") };
+                for value in record.into_values() {
+                    stack.push(value);
+                }
+                Push (reference.get(environment))
+            }
+
+            Spread => match stack.pop().and_then(lambdas) {
+                Some (functions) => match stack.pop_slice(functions.len()) {
+                    Some (values) => PushFrame (ControlFrame::Spread {
+                        applications: values.into_iter().zip(functions),
+                    }),
+                    None => Error ("Cannot perform `spread` unless there is a value below the list \
+                    for each of its lambdas".to_string()),
+                },
+                _ => Error ("Cannot perform `spread` unless there is a list of lambdas on top of \
+                the stack".to_string()),
+            }
+
+            Tri => match (stack.pop(), stack.pop(), stack.pop(), stack.pop()) {
                 (
-                    Some (Value::Lambda (reference)),
-                    Some (Value::List (list)),
-                    Some (accumulator),
-                ) => {
-                    let mut function: Function = Function::Composed(Vec::with_capacity(list.len()));
-                    stack.push(accumulator);
-                    for value in list {
+                    Some (Value::Lambda (h)),
+                    Some (Value::Lambda (g)),
+                    Some (Value::Lambda (f)),
+                    Some (value),
+                ) => cleaved(value, vec![f, g, h]),
+                _ => Error ("Cannot perform `tri` unless there are three lambdas above a value on \
+                top of the stack".to_string()),
+            }
+
+            Under => dipped(stack, environment, "under"),
+
+            // continuation combinators
+
+            Reset => match stack.pop() {
+                Some (Value::Lambda (reference)) => PushFrame (ControlFrame::Reset {
+                    body: Some (reference.get(environment)),
+                    depth: stack.size(),
+                }),
+                _ => Error ("Cannot perform `reset` unless there is a lambda on top of the stack"
+                    .to_string()),
+            }
+
+            Resume => match stack.pop() {
+                Some (Value::Continuation (continuation)) => ControlAction::Resume (continuation),
+                _ => Error ("Cannot perform `resume` unless there is a continuation on top of the \
+                stack".to_string()),
+            }
+
+            Shift => match stack.pop() {
+                Some (Value::Lambda (reference)) =>
+                    ControlAction::Shift (reference.get(environment)),
+                _ => Error ("Cannot perform `shift` unless there is a lambda on top of the stack"
+                    .to_string()),
+            }
+
+            // error handling combinators
+
+            Throw => match stack.pop() {
+                Some (value) => Raise (value),
+                None => Error ("Cannot perform `throw` on an empty stack".to_string()),
+            }
+
+            Try => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (handler)), Some (Value::Lambda (body))) => {
+                    stack.add_guard(stack.size());
+                    PushFrame (ControlFrame::Try {
+                        body: Some (body.get(environment)),
+                        depth: stack.size(),
+                        handler: handler.get(environment),
+                    })
+                }
+                _ => Error ("Cannot perform `try` unless there are two lambdas on top of the stack"
+                    .to_string()),
+            }
+
+            // iteration combinators
+
+            Each => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::List (items))) =>
+                    PushFrame (ControlFrame::Each {
+                        function: reference.get(environment),
+                        items: items.into_iter(),
+                    }),
+                _ => Error ("Cannot perform `each` unless there is a lambda above a list on top of \
+                the stack".to_string()),
+            }
+
+            Times => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::Integer (count))) =>
+                    PushFrame (ControlFrame::Times {
+                        function: reference.get(environment),
+                        remaining: count.as_count(),
+                    }),
+                _ => Error ("Cannot perform `times` unless there is a lambda above an integer on \
+                top of the stack".to_string()),
+            }
+
+            Until => conditional_loop(stack, environment, false, "until"),
+
+            While => conditional_loop(stack, environment, true, "while"),
+
+            // byte combinators
+
+            Base64ToBytes => bytes_operation(stack, decode_base64, "base64>bytes"),
+
+            BytesToBase64 => bytes_operation(stack, |bytes| Some (encode_base64(bytes)),
+                "bytes>base64"),
+
+            BytesToHexadecimal => bytes_operation(stack, |bytes| Some (encode_hexadecimal(bytes)),
+                "bytes>hex"),
+
+            BytesToList => match stack.pop() {
+                Some (Value::Bytes (bytes)) => {
+                    stack.push(Value::List (bytes.into_iter()
+                        .map(|byte| Value::Integer (Integer::from_usize(byte as usize)))
+                        .collect()
+                    ));
+                    Continue
+                }
+                _ => Error ("Cannot perform `bytes>list` unless there are bytes on top of the \
+                stack".to_string()),
+            }
+
+            DecodeBigEndian => decode_integer(stack, true, "decode-be"),
+
+            DecodeLittleEndian => decode_integer(stack, false, "decode-le"),
+
+            EncodeBigEndian => encode_integer(stack, true, "encode-be"),
+
+            EncodeLittleEndian => encode_integer(stack, false, "encode-le"),
+
+            HexadecimalToBytes => bytes_operation(stack, decode_hexadecimal, "hex>bytes"),
+
+            ListToBytes => match stack.pop() {
+                Some (Value::List (items)) => {
+                    let bytes: Option<Vec<u8>> = items.iter()
+                        .map(|item| match item {
+                            Value::Integer (integer) => integer.to_byte(),
+                            _ => None,
+                        })
+                        .collect();
+                    match bytes {
+                        Some (bytes) => {
+                            stack.push(Value::Bytes (bytes));
+                            Continue
+                        }
+                        None => Error ("Cannot perform `list>bytes` unless every item in the list \
+                        is an integer from 0 to 255".to_string()),
+                    }
+                }
+                _ => Error ("Cannot perform `list>bytes` unless there is a list on top of the \
+                stack".to_string()),
+            }
+
+            // map combinators
+
+            Entries => match stack.pop() {
+                Some (Value::Map (entries)) => {
+                    stack.push(Value::List (entries.into_iter()
+                        .map(|(key, value)| Value::List (vec![key, value]))
+                        .collect()
+                    ));
+                    Continue
+                }
+                _ => Error ("Cannot perform `entries` unless there is a map on top of the stack"
+                    .to_string()),
+            }
+
+            Get => match (stack.pop(), stack.pop()) {
+                (Some (key), Some (Value::Map (mut entries))) => match entries.remove(&key) {
+                    Some (value) => {
+                        stack.push(value);
+                        Continue
+                    }
+                    None => Error ("Cannot perform `get` with a key that is not in the map"
+                        .to_string()),
+                },
+                _ => Error ("Cannot perform `get` unless there is a map below the key on the stack"
+                    .to_string()),
+            }
+
+            Has => match (stack.pop(), stack.pop()) {
+                (Some (key), Some (Value::Map (entries))) => {
+                    stack.push(Value::Boolean (entries.contains_key(&key)));
+                    Continue
+                }
+                _ => Error ("Cannot perform `has?` unless there is a map below the key on the \
+                stack".to_string()),
+            }
+
+            Keys => match stack.pop() {
+                Some (Value::Map (entries)) => {
+                    stack.push(Value::List (entries.into_keys().collect()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `keys` unless there is a map on top of the stack"
+                    .to_string()),
+            }
+
+            MapValues => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::Map (entries))) => {
+                    let mut function: Function =
+                        Function::Composed(Vec::with_capacity(entries.len()));
+                    stack.push(Value::Map (BTreeMap::new()));
+                    for (key, value) in entries {
                         function = function.extended([
+                            Term::Data (key),
                             Term::Data (value),
                             Term::Data (Value::Lambda (reference.clone())),
                             Term::Combinator (Apply),
+                            Term::Combinator (Put)
                         ].into_iter())
                     }
                     Push (function)
                 }
-                _ => Error ("Cannot perform `fold` unless there is a lambda above a list above an \
-                accumulator on top of the stack".to_string()),
+                _ => Error ("Cannot perform `map-values` unless there is a lambda above a map on \
+                top of the stack".to_string()),
             }
 
-            Index => match (stack.pop(), stack.pop()) {
-                (Some (Value::Integer (integer)), Some (Value::List (items))) => {
-                    let index: usize = integer.as_wrapping_index(items.len());
-                    if index != usize::MAX {
-                        stack.push(items.get(index).unwrap().clone());
-                        Continue
-                    } else { Error ("Cannot perform `index` operation on an empty list"
-                        .to_string())}
-                    
+            Put => match (stack.pop(), stack.pop(), stack.pop()) {
+                (Some (value), Some (key), Some (Value::Map (mut entries))) => {
+                    entries.insert(key, value);
+                    stack.push(Value::Map (entries));
+                    Continue
                 }
-                _ => Error ("Cannot perform `index` operation unless there is an integer on \
-                    top of the stack, and a list below it".to_string())
-            },
+                _ => Error ("Cannot perform `put` unless there is a map below the key and value on \
+                the stack".to_string()),
+            }
+
+            Remove => match (stack.pop(), stack.pop()) {
+                (Some (key), Some (Value::Map (mut entries))) => {
+                    entries.remove(&key);
+                    stack.push(Value::Map (entries));
+                    Continue
+                }
+                _ => Error ("Cannot perform `remove` unless there is a map below the key on the \
+                stack".to_string()),
+            }
+
+            Values => match stack.pop() {
+                Some (Value::Map (entries)) => {
+                    stack.push(Value::List (entries.into_values().collect()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `values` unless there is a map on top of the stack"
+                    .to_string()),
+            }
+
+            // lazy sequence combinators
+
+            DropWhile => sequence_operation(stack, Sequence::DropWhile, "drop-while"),
+
+            Force => match stack.pop().and_then(Sequence::from_value) {
+                Some (sequence) => PushFrame (ControlFrame::Force {
+                    items: Vec::new(),
+                    sequence: Some (sequence),
+                }),
+                None => Error ("Cannot perform `force` unless there is a sequence or list on top \
+                of the stack".to_string()),
+            }
+
+            Iterate => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (step)), Some (seed)) => {
+                    stack.push(Value::Sequence (Sequence::Iterate (Box::new(seed), step)));
+                    Continue
+                }
+                _ => Error ("Cannot perform `iterate` unless there is a lambda above a seed value \
+                on top of the stack".to_string()),
+            }
+
+            LazyFilter => sequence_operation(stack, Sequence::Filter, "lazy-filter"),
+
+            LazyMap => sequence_operation(stack, Sequence::Map, "lazy-map"),
+
+            Next => match stack.pop().and_then(Sequence::from_value) {
+                Some (sequence) => PushFrame (ControlFrame::Next {
+                    sequence,
+                    state: Pull::Start,
+                }),
+                None => Error ("Cannot perform `next` unless there is a sequence or list on top \
+                of the stack".to_string()),
+            }
+
+            Take => match (stack.pop(), stack.pop().and_then(Sequence::from_value)) {
+                (Some (Value::Integer (count)), Some (sequence)) => {
+                    stack.push(Value::Sequence (Sequence::Take (
+                        Box::new(sequence),
+                        count.as_count()
+                    )));
+                    Continue
+                }
+                _ => Error ("Cannot perform `take` unless there is an integer above a sequence or \
+                list on top of the stack".to_string()),
+            }
+
+            TakeWhile => sequence_operation(stack, Sequence::TakeWhile, "take-while"),
+
+            // list combinators
+
+            Append => match (stack.pop(), stack.pop()) {
+                (Some (value), Some (Value::List (mut list_items))) => {
+                    list_items.push(value);
+                    stack.push(Value::List (list_items));
+                    Continue
+                }
+                _ => Error ("Cannot perform `append` unless there is a list below the value to \
+                be appended to it on the stack".to_string()),
+            }
+
+            Length => match stack.pop() {
+                Some (Value::List (items)) => {
+                    stack.push(Value::Integer (Integer::from_usize(items.len())));
+                    Continue
+                }
+                Some (Value::Bytes (bytes)) => {
+                    stack.push(Value::Integer (Integer::from_usize(bytes.len())));
+                    Continue
+                }
+                _ => Error ("Cannot perform `length` unless there is a list or bytes on top of \
+                the stack".to_string()),
+            },
+
+            All => predicate_fold(stack, Value::Boolean (true), And, "all"),
+
+            Any => predicate_fold(stack, Value::Boolean (false), Or, "any"),
+
+            Count => predicate_fold(stack, Value::Integer (Integer::from_usize(0)), MaybeIncrement,
+                "count"),
+
+            Enumerate => match stack.pop() {
+                Some (Value::List (items)) => {
+                    stack.push(Value::List (items.into_iter()
+                        .enumerate()
+                        .map(|(index, item)| Value::List (vec![
+                            Value::Integer (Integer::from_usize(index)),
+                            item
+                        ]))
+                        .collect()
+                    ));
+                    Continue
+                }
+                _ => Error ("Cannot perform `enumerate` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Filter => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::List (items))) =>
+                    PushFrame (ControlFrame::Filter {
+                        function: reference.get(environment),
+                        kept: Vec::with_capacity(items.len()),
+                        items: items.into_iter(),
+                        pending: None,
+                    }),
+                _ => Error ("Cannot perform `filter` unless there is a lambda above a list on top \
+                of the stack".to_string()),
+            }
+
+            First => selected_item(stack, |mut items| items.next(), "first"),
+
+            Flatten => match stack.pop() {
+                Some (Value::List (items)) => {
+                    let mut flattened: Vec<Value> = Vec::with_capacity(items.len());
+                    for item in items {
+                        match item {
+                            Value::List (inner) => flattened.extend(inner),
+                            item => flattened.push(item),
+                        }
+                    }
+                    stack.push(Value::List (flattened));
+                    Continue
+                }
+                _ => Error ("Cannot perform `flatten` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Fold => match (stack.pop(), stack.pop(), stack.pop()) {
+                (
+                    Some (Value::Lambda (reference)),
+                    Some (Value::List (list)),
+                    Some (accumulator),
+                ) => {
+                    // folding applies the lambda to each item above the accumulator, as `each` does
+                    stack.push(accumulator);
+                    PushFrame (ControlFrame::Each {
+                        function: reference.get(environment),
+                        items: list.into_iter(),
+                    })
+                }
+                _ => Error ("Cannot perform `fold` unless there is a lambda above a list above an \
+                accumulator on top of the stack".to_string()),
+            }
+
+            Index => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (integer)), Some (Value::List (items))) => {
+                    let index: usize = integer.as_wrapping_index(items.len());
+                    if index != usize::MAX {
+                        stack.push(items.get(index).unwrap().clone());
+                        Continue
+                    } else { Error ("Cannot perform `index` operation on an empty list"
+                        .to_string())}
+                    
+                }
+                (Some (Value::Integer (integer)), Some (Value::Bytes (bytes))) => {
+                    let index: usize = integer.as_wrapping_index(bytes.len());
+                    if index != usize::MAX {
+                        stack.push(Value::Integer (Integer::from_usize(bytes[index] as usize)));
+                        Continue
+                    } else { Error ("Cannot perform `index` operation on empty bytes"
+                        .to_string())}
+                }
+                _ => Error ("Cannot perform `index` operation unless there is an integer on \
+                    top of the stack, and a list or bytes below it".to_string())
+            },
 
             Join => match (stack.pop(), stack.pop()) {
                 (Some (Value::List (list_b)), Some (Value::List (mut list_a))) => {
@@ -534,112 +2184,755 @@ impl Combinator {
                     stack.push(Value::List (list_a));
                     Continue
                 }
-                _ => Error ("Cannot perform `join` unless there are two lists on top of the stack"
+                (Some (Value::Bytes (bytes_b)), Some (Value::Bytes (mut bytes_a))) => {
+                    bytes_a.extend(bytes_b);
+                    stack.push(Value::Bytes (bytes_a));
+                    Continue
+                }
+                _ => Error ("Cannot perform `join` unless there are two lists or two bytes on top \
+                of the stack".to_string()),
+            }
+
+            Iota => match stack.pop() {
+                Some (Value::Integer (end)) => {
+                    let start: Integer = Integer::from_usize(0);
+                    integer_range(stack, start, end, Integer::from_usize(1), "iota")
+                }
+                _ => Error ("Cannot perform `iota` unless there is an integer on top of the stack"
+                    .to_string()),
+            }
+
+            Last => selected_item(stack, Iterator::last, "last"),
+
+            MaybeIncrement => match (stack.pop(), stack.pop()) {
+                (Some (Value::Boolean (holds)), Some (Value::Integer (count))) => {
+                    let increment: usize = if holds { 1 } else { 0 };
+                    stack.push(Value::Integer (count + Integer::from_usize(increment)));
+                    Continue
+                }
+                _ => Error ("Cannot perform `count` unless its predicate evaluates to a boolean"
+                    .to_string()),
+            }
+
+            MaybeAppend => match (stack.pop(), stack.pop(), stack.pop()) {
+                (Some (value), Some (Value::Boolean (boolean)), Some (Value::List (mut items))) => {
+                    if boolean {
+                        items.push(value);
+                    }
+                    stack.push(Value::List (items));
+                    Continue
+                }
+                _ => Error ("Internal combinator error".to_string())
+            }
+
+            Map => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::List (items))) =>
+                    PushFrame (ControlFrame::Map {
+                        function: reference.get(environment),
+                        mapped: Vec::with_capacity(items.len()),
+                        items: items.into_iter(),
+                        pending: false,
+                    }),
+                _ => Error ("Cannot perform `map` unless there is a lambda above a list on top of \
+                stack".to_string()),
+            }
+            
+            Maximum => selected_item(stack, Iterator::max, "max"),
+
+            Minimum => selected_item(stack, Iterator::min, "min"),
+
+            Pack => match stack.pop() {
+                Some (Value::Integer (count)) => match count.to_usize()
+                    .and_then(|count| stack.pop_slice(count)) {
+                    Some (items) => {
+                        stack.push(Value::List (items));
+                        Continue
+                    }
+                    None => Error ("Cannot perform `pack` unless there are as many items below \
+                    the count as it asks for".to_string()),
+                },
+                _ => Error ("Cannot perform `pack` unless there is an integer on top of the stack"
                     .to_string()),
             }
 
-            MaybeAppend => match (stack.pop(), stack.pop(), stack.pop()) {
-                (Some (value), Some (Value::Boolean (boolean)), Some (Value::List (mut items))) => {
-                    if boolean {
-                        items.push(value);
-                    }
-                    stack.push(Value::List (items));
+            Product => numeric_fold(
+                stack,
+                Value::Integer (Integer::from_usize(1)),
+                |a, b| Value::Integer (a * b),
+                |a, b| a * b,
+                |a, b| a * b,
+                "product",
+            ),
+
+            Range => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (end)), Some (Value::Integer (start))) =>
+                    integer_range(stack, start, end, Integer::from_usize(1), "range"),
+                (Some (Value::Float (end)), Some (Value::Integer (start))) =>
+                    unbounded_range(stack, start, end, Integer::from_usize(1), "range"),
+                _ => Error ("Cannot perform `range` unless there are two integers on top of the \
+                stack".to_string()),
+            }
+
+            RangeStep => match (stack.pop(), stack.pop(), stack.pop()) {
+                (
+                    Some (Value::Integer (step)),
+                    Some (Value::Integer (end)),
+                    Some (Value::Integer (start)),
+                ) => integer_range(stack, start, end, step, "range-step"),
+                (
+                    Some (Value::Integer (step)),
+                    Some (Value::Float (end)),
+                    Some (Value::Integer (start)),
+                ) => unbounded_range(stack, start, end, step, "range-step"),
+                _ => Error ("Cannot perform `range-step` unless there are three integers on top \
+                of the stack".to_string()),
+            }
+
+            Repeat => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (count)), Some (value)) => match generated_length(&count) {
+                    Some (count) => {
+                        stack.push(Value::List (vec![value; count]));
+                        Continue
+                    }
+                    None => Error (format!("Cannot perform `repeat` to make a list of more than \
+                    {} items", MAXIMUM_GENERATED_ITEMS)),
+                },
+                _ => Error ("Cannot perform `repeat` unless there is an integer above a value on \
+                top of the stack".to_string()),
+            }
+
+            Rest => match stack.pop() {
+                Some (Value::List (mut items)) if !items.is_empty() => {
+                    items.remove(0);
+                    stack.push(Value::List (items));
+                    Continue
+                }
+                Some (Value::List (_)) => Error ("Cannot perform `rest` on an empty list"
+                    .to_string()),
+                _ => Error ("Cannot perform `rest` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Reverse => match stack.pop() {
+                Some (Value::List (mut items)) => {
+                    items.reverse();
+                    stack.push(Value::List (items));
+                    Continue
+                }
+                _ => Error ("Cannot perform `reverse` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Slice => match (stack.pop(), stack.pop(), stack.pop()) {
+                (
+                    Some (Value::Integer (end)),
+                    Some (Value::Integer (start)),
+                    Some (Value::List (items)),
+                ) => {
+                    let start: usize = start.as_slice_bound(items.len());
+                    let end: usize = end.as_slice_bound(items.len()).max(start);
+                    stack.push(Value::List (items[start..end].to_vec()));
+                    Continue
+                }
+                (
+                    Some (Value::Integer (end)),
+                    Some (Value::Integer (start)),
+                    Some (Value::Bytes (bytes)),
+                ) => {
+                    let start: usize = start.as_slice_bound(bytes.len());
+                    let end: usize = end.as_slice_bound(bytes.len()).max(start);
+                    stack.push(Value::Bytes (bytes[start..end].to_vec()));
+                    Continue
+                }
+                _ => Error ("Cannot perform `slice` unless there are two integers above a list or \
+                bytes on top of the stack".to_string()),
+            }
+
+            Sort => match stack.pop() {
+                Some (Value::List (mut items)) => {
+                    items.sort();
+                    stack.push(Value::List (items));
+                    Continue
+                }
+                _ => Error ("Cannot perform `sort` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            SortBy => match (stack.pop(), stack.pop()) {
+                (Some (Value::Lambda (reference)), Some (Value::List (items))) =>
+                    PushFrame (ControlFrame::SortBy {
+                        function: reference.get(environment),
+                        keyed: Vec::with_capacity(items.len()),
+                        items: items.into_iter(),
+                        pending: None,
+                    }),
+                _ => Error ("Cannot perform `sort-by` unless there is a lambda above a list on top \
+                of the stack".to_string()),
+            }
+
+            SplitAt => match (stack.pop(), stack.pop()) {
+                (Some (Value::Integer (index)), Some (Value::List (mut items))) => {
+                    let rest: Vec<Value> = items.split_off(index.as_slice_bound(items.len()));
+                    stack.push(Value::List (items));
+                    stack.push(Value::List (rest));
+                    Continue
+                }
+                _ => Error ("Cannot perform `split-at` unless there is an integer above a list on \
+                top of the stack".to_string()),
+            }
+
+            Sum => numeric_fold(
+                stack,
+                Value::Integer (Integer::from_usize(0)),
+                |a, b| Value::Integer (a + b),
+                |a, b| a + b,
+                |a, b| a + b,
+                "sum",
+            ),
+
+            Uncons => match stack.pop() {
+                Some (Value::List (mut items)) if !items.is_empty() => {
+                    let first: Value = items.remove(0);
+                    stack.push(first);
+                    stack.push(Value::List (items));
+                    Continue
+                }
+                Some (Value::List (_)) => Error ("Cannot perform `uncons` on an empty list"
+                    .to_string()),
+                _ => Error ("Cannot perform `uncons` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Unique => match stack.pop() {
+                Some (Value::List (items)) => {
+                    let mut seen: BTreeSet<Value> = BTreeSet::new();
+                    stack.push(Value::List (items.into_iter()
+                        .filter(|item| seen.insert(item.clone()))
+                        .collect()
+                    ));
+                    Continue
+                }
+                _ => Error ("Cannot perform `unique` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Unpack => match stack.pop() {
+                Some (Value::List (items)) => {
+                    for item in items {
+                        stack.push(item);
+                    }
+                    Continue
+                }
+                _ => Error ("Cannot perform `unpack` unless there is a list on top of the stack"
+                    .to_string()),
+            }
+
+            Zip => match (stack.pop(), stack.pop()) {
+                (Some (Value::List (right)), Some (Value::List (left))) => {
+                    stack.push(Value::List (left.into_iter()
+                        .zip(right)
+                        .map(|(left, right)| Value::List (vec![left, right]))
+                        .collect()
+                    ));
+                    Continue
+                }
+                _ => Error ("Cannot perform `zip` unless there are two lists on top of the stack"
+                    .to_string()),
+            }
+
+            // stack manipulation combinators
+
+            Copy => if let Some(top) = stack.get_from_top(0) {
+                stack.push(top.clone());
+                Continue
+            } else { Error ("No items the in stack to be copied".to_string()) },
+    
+            Drop => if let Some(_) = stack.pop() {
+                Continue
+            } else { Error ("No items in the stack to be dropped".to_string()) },
+    
+            Hop => if let Some(top) = stack.get_from_top(1) {
+                stack.push(top.clone());
+                Continue
+            } else { Error ("Not enough items in the stack to be hopped".to_string()) },
+
+            Pick => if let Some (Value::Integer (integer)) = stack.pop() {
+                let Some (indexed) = stack.get_from_top(integer.as_wrapping_index(stack.size()))
+                else { return Error ("Cannot perform `pick` on an empty stack.".to_string()); } ;
+                stack.push(indexed.clone());
+                Continue
+            } else { Error ("Missing integer on top of the stack to represent index for `pick` \
+            operation".to_string()) },
+    
+            Rotate => if stack.size() < 3 {
+                Error ("Not enough items in the stack to rotate".to_string())
+            } else {
+                let a: &mut Value = stack.get_mutable_from_top(2).unwrap();
+                let b: &mut Value = stack.get_mutable_from_top(1).unwrap();
+                let c: &mut Value = stack.get_mutable_from_top(0).unwrap();
+                swap(a, c);
+                swap(b, c);
+                Continue
+            },
+    
+            Swap => if stack.size() < 2 {
+                Error ("Not enough items in the stack to swap".to_string())
+            } else {
+                swap(
+                    stack.get_mutable_from_top(1).unwrap(),
+                    stack.get_mutable_from_top(0).unwrap(),
+                );
+                Continue
+            }
+
+        }
+    }
+
+}
+
+
+
+/// Evaluates an arithmetic operation on a `VirtualMachine`, promoting integers to rationals if
+/// either number is a rational, and refusing to mix floats with exact numbers
+fn arithmetic_operation<'a>(
+    stack: &mut DataStack,
+    integers: fn(Integer, Integer) -> Value,
+    rationals: fn(Rational, Rational) -> Rational,
+    floats: fn(Float, Float) -> Float,
+) -> ControlAction<'a> {
+    if stack.size() < 2 {
+        Error("Not enough items in the stack to perform arithmetic operation"
+            .to_string())
+    } else {
+        let b: Value = stack.pop().unwrap();
+        let a: Value = stack.pop().unwrap();
+        match arithmetic(a, b, integers, rationals, floats) {
+            Ok (result) => {
+                stack.push(result);
+                Continue
+            }
+            Err (error) => Error (error),
+        }
+    }
+}
+
+
+
+/// Performs an arithmetic operation on two numbers, promoting integers to rationals if either
+/// number is a rational, and refusing to mix floats with exact numbers
+fn arithmetic(
+    a: Value,
+    b: Value,
+    integers: fn(Integer, Integer) -> Value,
+    rationals: fn(Rational, Rational) -> Rational,
+    floats: fn(Float, Float) -> Float,
+) -> Result<Value, String> {
+    Ok (match (b, a) {
+        (Value::Integer (b), Value::Integer (a)) => integers(a, b),
+        (Value::Integer (b), Value::Rational (a)) =>
+            Value::from_rational(rationals(a, Rational::from_integer(b))),
+        (Value::Rational (b), Value::Integer (a)) =>
+            Value::from_rational(rationals(Rational::from_integer(a), b)),
+        (Value::Rational (b), Value::Rational (a)) => Value::from_rational(rationals(a, b)),
+        (Value::Float (b), Value::Float (a)) => Value::Float (floats(a, b)),
+        (Value::Float (_), Value::Integer (_) | Value::Rational (_))
+        | (Value::Integer (_) | Value::Rational (_), Value::Float (_)) =>
+            return Err ("Cannot perform arithmetic operation on a float and an exact number \
+            without converting one of them with `int>float` or `float>int`".to_string()),
+        _ => return Err ("Can only perform arithmetic operation on numbers".to_string())
+    })
+}
+
+
+
+/// Turns a list of numbers on top of the stack into one number by combining them with an
+/// arithmetic operation, or into an identity number if the list is empty
+fn numeric_fold<'a>(
+    stack: &mut DataStack,
+    identity: Value,
+    integers: fn(Integer, Integer) -> Value,
+    rationals: fn(Rational, Rational) -> Rational,
+    floats: fn(Float, Float) -> Float,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::List (items)) => {
+            let mut items: IntoIter<Value> = items.into_iter();
+            // start from the first item so that lists of floats are not mixed with the identity
+            let first: Value = match items.next() {
+                Some (first @ (Value::Float (_) | Value::Integer (_) | Value::Rational (_))) =>
+                    first,
+                Some (_) => return Error ("Can only perform arithmetic operation on numbers"
+                    .to_string()),
+                None => identity,
+            };
+            let result: Result<Value, String> = items
+                .try_fold(first, |a, b| arithmetic(a, b, integers, rationals, floats));
+            match result {
+                Ok (result) => {
+                    stack.push(result);
+                    Continue
+                }
+                Err (error) => Error (error),
+            }
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is a list on top of the stack",
+            name)),
+    }
+}
+
+
+
+/// Returns the number of items in a generated list from the integer it is made from, treating
+/// negative integers as zero, or returns `None` if the list would have too many items
+fn generated_length(count: &Integer) -> Option<usize> {
+    if count.is_negative() { return Some (0); }
+    count.to_usize().filter(|count| *count <= MAXIMUM_GENERATED_ITEMS)
+}
+
+
+
+/// Pushes a list of the integers from a start counting by a step for as long as they are before
+/// an end
+fn integer_range<'a>(
+    stack: &mut DataStack,
+    start: Integer,
+    end: Integer,
+    step: Integer,
+    name: &str,
+) -> ControlAction<'a> {
+    if step.is_zero() {
+        return Error (format!("Cannot perform `{}` with a step of zero", name));
+    }
+    let distance: Integer = if step.is_negative() {
+        start.clone() - end
+    } else { end - start.clone() };
+    let magnitude: Integer = step.abs();
+    // the number of steps that land before the end, rounding up
+    let count: Integer = (distance + magnitude.clone() - Integer::from_usize(1))
+        .div_floor(&magnitude);
+    let Some (count) = generated_length(&count) else {
+        return Error (format!("Cannot perform `{}` to make a list of more than {} items", name,
+            MAXIMUM_GENERATED_ITEMS));
+    };
+    let mut items: Vec<Value> = Vec::with_capacity(count);
+    let mut current: Integer = start;
+    for _ in 0..count {
+        let next: Integer = current.clone() + step.clone();
+        items.push(Value::Integer (current));
+        current = next;
+    }
+    stack.push(Value::List (items));
+    Continue
+}
+
+
+
+/// Pushes a `Sequence` of integers that counts from a start by a step without end if the end is
+/// an infinite float in the direction of the step, or an empty list if it is in the other
+/// direction
+fn unbounded_range<'a>(
+    stack: &mut DataStack,
+    start: Integer,
+    end: Float,
+    step: Integer,
+    name: &str,
+) -> ControlAction<'a> {
+    if step.is_zero() {
+        return Error (format!("Cannot perform `{}` with a step of zero", name));
+    }
+    if !end.value().is_infinite() {
+        return Error (format!("Cannot perform `{}` with a finite float as its end", name));
+    }
+    if (end.value() < 0.0) == step.is_negative() {
+        stack.push(Value::Sequence (Sequence::Range (start, step)));
+    } else { stack.push(Value::List (vec![])); }
+    Continue
+}
+
+
+
+/// Turns a list and a predicate lambda on top of the stack into a value by combining the results
+/// of the predicate for each item with a combinator as it iterates, starting from an initial value
+fn predicate_fold<'a>(
+    stack: &mut DataStack,
+    initial: Value,
+    combinator: Combinator,
+    name: &str,
+) -> ControlAction<'a> {
+    match (stack.pop(), stack.pop()) {
+        (Some (Value::Lambda (reference)), Some (Value::List (list))) => {
+            let mut function: Function = Function::Composed(Vec::with_capacity(list.len()));
+            stack.push(initial);
+            for value in list {
+                function = function.extended([
+                    Term::Data (value),
+                    Term::Data (Value::Lambda (reference.clone())),
+                    Term::Combinator (Combinator::Apply),
+                    Term::Combinator (combinator),
+                ].into_iter())
+            }
+            Push (function)
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is a lambda above a list on top of \
+        the stack", name)),
+    }
+}
+
+
+
+/// Checks whether the number on top of the stack is zero, so that it cannot be used as a divisor
+fn divisor_is_zero(stack: &DataStack) -> bool {
+    match stack.get_from_top(0) {
+        Some (Value::Integer (integer)) => *integer == Integer::from_usize(0),
+        Some (Value::Rational (rational)) => rational.is_zero(),
+        _ => false,
+    }
+}
+
+
+
+/// Evaluates a fallible operation on the two integers on top of the stack
+fn integer_operation<'a>(
+    stack: &mut DataStack,
+    operation: fn(Integer, Integer) -> Result<Integer, &'static str>,
+    name: &str,
+) -> ControlAction<'a> {
+    match (stack.pop(), stack.pop()) {
+        (Some (Value::Integer (b)), Some (Value::Integer (a))) => match operation(a, b) {
+            Ok (integer) => {
+                stack.push(Value::Integer (integer));
+                Continue
+            }
+            Err (error) => Error (error.to_string()),
+        },
+        _ => Error (format!("Cannot perform `{}` unless there are two integers on top of the \
+        stack", name)),
+    }
+}
+
+
+
+/// Evaluates a fallible operation on the integer on top of the stack
+fn unary_integer_operation<'a>(
+    stack: &mut DataStack,
+    operation: fn(Integer) -> Result<Integer, &'static str>,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::Integer (integer)) => match operation(integer) {
+            Ok (integer) => {
+                stack.push(Value::Integer (integer));
+                Continue
+            }
+            Err (error) => Error (error.to_string()),
+        },
+        _ => Error (format!("Cannot perform `{}` unless there is an integer on top of the stack",
+            name)),
+    }
+}
+
+
+
+/// Evaluates a function of a float on top of the stack
+fn float_operation<'a>(
+    stack: &mut DataStack,
+    operation: fn(f64) -> f64,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::Float (float)) => {
+            stack.push(Value::Float (Float::new(operation(float.value()))));
+            Continue
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is a float on top of the stack",
+            name)),
+    }
+}
+
+
+
+/// Turns the number on top of the stack into an integer derived from it as a rational
+fn rounding_operation<'a>(
+    stack: &mut DataStack,
+    operation: fn(&Rational) -> Integer,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::Integer (integer)) => {
+            stack.push(Value::Integer (operation(&Rational::from_integer(integer))));
+            Continue
+        }
+        Some (Value::Rational (rational)) => {
+            stack.push(Value::Integer (operation(&rational)));
+            Continue
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is an integer or rational on top \
+        of the stack", name)),
+    }
+}
+
+
+
+/// Applies each of a number of lambdas in order to a value
+fn cleaved<'a>(value: Value, functions: Vec<LambdaReference>) -> ControlAction<'a> {
+    PushFrame (ControlFrame::Cleave { functions: functions.into_iter(), value })
+}
+
+
+
+/// Applies the lambda on top of the stack to the stack below the value under it, and then pushes
+/// that value back to the stack
+fn dipped<'a>(
+    stack: &mut DataStack,
+    environment: &'a Environment,
+    name: &str,
+) -> ControlAction<'a> {
+    match (stack.pop(), stack.pop()) {
+        (Some (Value::Lambda (reference)), Some (top)) =>
+            Push (reference.get(environment).extended([Term::Data (top)].into_iter())),
+        _ => Error (format!("Cannot perform `{}` unless there is a lambda above a value on top of \
+        the stack", name)),
+    }
+}
+
+
+
+/// Returns the lambdas in a list, or returns `None` if the value is not a list of lambdas
+fn lambdas(value: Value) -> Option<Vec<LambdaReference>> {
+    match value {
+        Value::List (items) => items.into_iter()
+            .map(|item| match item {
+                Value::Lambda (reference) => Some (reference),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+
+
+/// Evaluates a loop that applies a body function for as long as a condition function evaluates to
+/// an expected boolean
+fn conditional_loop<'a>(
+    stack: &mut DataStack,
+    environment: &'a Environment,
+    expected: bool,
+    name: &str,
+) -> ControlAction<'a> {
+    match (stack.pop(), stack.pop()) {
+        (Some (Value::Lambda (body)), Some (Value::Lambda (condition))) =>
+            PushFrame (ControlFrame::While {
+                body: body.get(environment),
+                condition: condition.get(environment),
+                expected,
+                testing: false,
+            }),
+        _ => Error (format!("Cannot perform `{}` unless there are two lambdas on top of the \
+        stack", name)),
+    }
+}
+
+
+
+/// Transforms bytes on top of the stack with an encoding or decoding that may fail
+fn bytes_operation<'a>(
+    stack: &mut DataStack,
+    operation: fn(&[u8]) -> Option<Vec<u8>>,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::Bytes (bytes)) => match operation(&bytes) {
+            Some (bytes) => {
+                stack.push(Value::Bytes (bytes));
+                Continue
+            }
+            None => Error (format!("Cannot perform `{}` on malformed bytes", name)),
+        },
+        _ => Error (format!("Cannot perform `{}` unless there are bytes on top of the stack",
+            name)),
+    }
+}
+
+
+
+/// Turns bytes on top of the stack into the non-negative integer they encode
+fn decode_integer<'a>(
+    stack: &mut DataStack,
+    big_endian: bool,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::Bytes (bytes)) => {
+            stack.push(Value::Integer (Integer::from_bytes(&bytes, big_endian)));
+            Continue
+        }
+        _ => Error (format!("Cannot perform `{}` unless there are bytes on top of the stack",
+            name)),
+    }
+}
+
+
+
+/// Encodes a non-negative integer as bytes of the width on top of the stack
+fn encode_integer<'a>(
+    stack: &mut DataStack,
+    big_endian: bool,
+    name: &str,
+) -> ControlAction<'a> {
+    match (stack.pop(), stack.pop()) {
+        (Some (Value::Integer (width)), Some (Value::Integer (integer))) => {
+            match width.to_usize().and_then(|width| integer.to_bytes(width, big_endian)) {
+                Some (bytes) => {
+                    stack.push(Value::Bytes (bytes));
                     Continue
                 }
-                _ => Error ("Internal combinator error".to_string())
+                None => Error (format!("Cannot perform `{}` unless the integer is non-negative \
+                and fits in the width", name)),
             }
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is a width above an integer on top \
+        of the stack", name)),
+    }
+}
 
-            Map => match (stack.pop(), stack.pop()) {
-                (Some (Value::Lambda (reference)), Some (Value::List (list))) => {
-                    let mut function: Function = Function::Composed(Vec::with_capacity(list.len()));
-                    stack.push(Value::List(Vec::with_capacity(list.len())));
-                    for value in list {
-                        function = function.extended([
-                            Term::Data (value),
-                            Term::Data (Value::Lambda (reference.clone())),
-                            Term::Combinator (Apply),
-                            Term::Combinator (Append)
-                        ].into_iter())
-                    }
-                    Push (function)
-                }
-                _ => Error ("Cannot perform `map` unless there is a lambda above a list on top of \
-                stack".to_string()),
-            }
-            
-            // stack manipulation combinators
 
-            Copy => if let Some(top) = stack.get_from_top(0) {
-                stack.push(top.clone());
-                Continue
-            } else { Error ("No items the in stack to be copied".to_string()) },
-    
-            Drop => if let Some(_) = stack.pop() {
-                Continue
-            } else { Error ("No items in the stack to be dropped".to_string()) },
-    
-            Hop => if let Some(top) = stack.get_from_top(1) {
-                stack.push(top.clone());
-                Continue
-            } else { Error ("Not enough items in the stack to be hopped".to_string()) },
 
-            Pick => if let Some (Value::Integer (integer)) = stack.pop() {
-                let Some (indexed) = stack.get_from_top(integer.as_wrapping_index(stack.size()))
-                else { return Error ("Cannot perform `pick` on an empty stack.".to_string()); } ;
-                stack.push(indexed.clone());
-                Continue
-            } else { Error ("Missing integer on top of the stack to represent index for `pick` \
-            operation".to_string()) },
-    
-            Rotate => if stack.size() < 3 {
-                Error ("Not enough items in the stack to rotate".to_string())
-            } else {
-                let a: &mut Value = stack.get_mutable_from_top(2).unwrap();
-                let b: &mut Value = stack.get_mutable_from_top(1).unwrap();
-                let c: &mut Value = stack.get_mutable_from_top(0).unwrap();
-                swap(a, c);
-                swap(b, c);
-                Continue
-            },
-    
-            Swap => if stack.size() < 2 {
-                Error ("Not enough items in the stack to swap".to_string())
-            } else {
-                swap(
-                    stack.get_mutable_from_top(0).unwrap(),
-                    stack.get_mutable_from_top(1).unwrap(),
-                );
+/// Turns a non-empty list on top of the stack into one of its items
+fn selected_item<'a>(
+    stack: &mut DataStack,
+    select: fn(IntoIter<Value>) -> Option<Value>,
+    name: &str,
+) -> ControlAction<'a> {
+    match stack.pop() {
+        Some (Value::List (items)) => match select(items.into_iter()) {
+            Some (item) => {
+                stack.push(item);
                 Continue
             }
-
-        }
+            None => Error (format!("Cannot perform `{}` on an empty list", name)),
+        },
+        _ => Error (format!("Cannot perform `{}` unless there is a list on top of the stack",
+            name)),
     }
-
 }
 
 
 
-/// Evaluates an arithmetic operation on a `VirtualMachine`
-fn arithmetic_operation<'a>(
+/// Creates a lazy `Sequence` from a lambda above a sequence or list on top of the stack
+fn sequence_operation<'a>(
     stack: &mut DataStack,
-    operation: fn(Integer, Integer) -> Integer
+    constructor: fn(Box<Sequence>, LambdaReference) -> Sequence,
+    name: &str,
 ) -> ControlAction<'a> {
-    if stack.size() < 2 {
-        Error("Not enough items in the stack to perform arithmetic operation"
-            .to_string())
-    } else {
-        let (b, a): (Integer, Integer) = match (
-            stack.pop().unwrap(),
-            stack.pop().unwrap()
-        ) {
-            (Value::Integer(b), Value::Integer(a)) => {
-                (b, a)
-            }
-            _ => return Error("Can only perform arithmetic operation on integers"
-                .to_string())
-        };
-        stack.push(Value::Integer(operation(a, b)));
-        Continue
+    match (stack.pop(), stack.pop().and_then(Sequence::from_value)) {
+        (Some (Value::Lambda (function)), Some (sequence)) => {
+            stack.push(Value::Sequence (constructor(Box::new(sequence), function)));
+            Continue
+        }
+        _ => Error (format!("Cannot perform `{}` unless there is a lambda above a sequence or \
+        list on top of the stack", name)),
     }
 }
 
@@ -685,4 +2978,803 @@ fn comparison_operation<'a>(
         }));
         Continue
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::{
+        generated_length,
+        MAXIMUM_GENERATED_ITEMS,
+    };
+    use crate::{
+        Integer,
+        Namespace,
+        testing::{
+            define,
+            evaluate_bare,
+            evaluate_in,
+        },
+    };
+
+    #[test]
+    fn times_applies_a_function_a_number_of_times() {
+        assert_eq!(evaluate_bare("0 3 ( 1 + ) times"), Ok ("3".to_string()));
+        assert_eq!(evaluate_bare("0 3 ( 1 ) times"), Ok ("0 1 1 1".to_string()));
+        assert_eq!(evaluate_bare("0 0 ( 1 + ) times"), Ok ("0".to_string()));
+        assert_eq!(evaluate_bare("0 -2 ( 1 + ) times"), Ok ("0".to_string()));
+    }
+
+    #[test]
+    fn times_runs_in_constant_space() {
+        assert_eq!(evaluate_bare("0 1000000 ( 1 + ) times"), Ok ("1000000".to_string()));
+    }
+
+    #[test]
+    fn times_fails_without_a_lambda_and_a_count() {
+        assert_eq!(
+            evaluate_bare("0 3 2 times"),
+            Err ("Cannot perform `times` unless there is a lambda above an integer on top of the \
+            stack".to_string())
+        );
+    }
+
+    #[test]
+    fn each_applies_a_function_to_every_item() {
+        assert_eq!(evaluate_bare("0 [ 1 2 3 ] ( + ) each"), Ok ("6".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 ] ( 10 * ) each"), Ok ("10 20".to_string()));
+        assert_eq!(evaluate_bare("[ ] ( drop ) each"), Ok ("".to_string()));
+    }
+
+    #[test]
+    fn map_filter_and_fold_apply_a_function_to_every_item() {
+        assert_eq!(evaluate_bare("[ 1 2 3 ] ( 2 * ) map"), Ok ("[ 2 4 6 ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 4 ] ( 2 % 0 = ) filter"), Ok ("[ 2 4 ]".to_string()));
+        assert_eq!(evaluate_bare("0 [ 1 2 3 ] ( + ) fold"), Ok ("6".to_string()));
+        assert_eq!(
+            evaluate_bare("[ 1 2 ] ( 1 + ) ( 2 * ) compose map"),
+            Ok ("[ 4 6 ]".to_string())
+        );
+        assert_eq!(evaluate_bare("[ ] ( 1 + ) map [ ] ( ) filter"), Ok ("[ ] [ ]".to_string()));
+    }
+
+    #[test]
+    fn map_and_filter_need_a_result_for_every_item() {
+        assert_eq!(
+            evaluate_bare("[ 1 2 ] ( drop ) map"),
+            Err ("Function of `map` must leave a result on the stack".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ 1 2 ] ( ) filter"),
+            Err ("Predicate of `filter` must evaluate to a boolean".to_string())
+        );
+    }
+
+    #[test]
+    fn map_can_be_resumed_from_a_continuation() {
+        assert_eq!(
+            evaluate_bare("( [ 1 2 3 ] ( copy 2 = ( ( ) shift ) ( ) ? 10 * ) map ) reset resume"),
+            Ok ("[ 10 20 30 ]".to_string())
+        );
+    }
+
+    #[test]
+    fn while_loops_until_its_condition_is_false() {
+        assert_eq!(evaluate_bare("0 ( copy 5 < ) ( 1 + ) while"), Ok ("5".to_string()));
+        assert_eq!(evaluate_bare("10 ( copy 5 < ) ( 1 + ) while"), Ok ("10".to_string()));
+    }
+
+    #[test]
+    fn until_loops_until_its_condition_is_true() {
+        assert_eq!(evaluate_bare("0 ( copy 5 > ) ( 1 + ) until"), Ok ("6".to_string()));
+        assert_eq!(evaluate_bare("10 ( copy 5 > ) ( 1 + ) until"), Ok ("10".to_string()));
+    }
+
+    #[test]
+    fn loop_conditions_must_be_booleans() {
+        assert_eq!(
+            evaluate_bare("1 ( ) ( ) while"),
+            Err ("Loop condition must evaluate to a boolean".to_string())
+        );
+    }
+
+    #[test]
+    fn try_leaves_the_results_of_a_body_that_succeeds() {
+        assert_eq!(evaluate_bare("1 2 3 ( + + ) ( ) try"), Ok ("6".to_string()));
+    }
+
+    #[test]
+    fn try_restores_values_consumed_by_a_body_that_fails() {
+        assert_eq!(
+            evaluate_bare("1 2 3 ( drop drop 9 throw ) ( ) try"),
+            Ok ("1 2 3 9".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 2 3 ( 4 5 drop drop drop 9 throw ) ( ) try"),
+            Ok ("1 2 3 9".to_string())
+        );
+    }
+
+    #[test]
+    fn try_restores_values_changed_in_place_by_a_body_that_fails() {
+        assert_eq!(evaluate_bare("1 2 3 ( swap 0 throw ) ( ) try"), Ok ("1 2 3 0".to_string()));
+        assert_eq!(evaluate_bare("1 2 3 ( rotate 0 throw ) ( ) try"), Ok ("1 2 3 0".to_string()));
+    }
+
+    #[test]
+    fn try_applies_its_handler_to_errors_raised_by_combinators() {
+        assert_eq!(
+            evaluate_bare("1 ( + ) ( ) try"),
+            Ok ("1 error(\"Not enough items in the stack to perform arithmetic operation\")"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn nested_trys_restore_the_stack_they_were_entered_with() {
+        assert_eq!(
+            evaluate_bare("1 2 ( drop ( drop 5 throw ) ( ) try ) ( ) try"),
+            Ok ("1 5".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 2 3 ( drop ( drop drop 4 ) ( ) try 5 throw ) ( ) try"),
+            Ok ("1 2 3 5".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 2 ( ( drop drop 5 throw ) ( 7 ) try 0 throw ) ( ) try"),
+            Ok ("1 2 0".to_string())
+        );
+    }
+
+    #[test]
+    fn try_restores_the_stack_after_its_continuation_is_resumed() {
+        assert_eq!(
+            evaluate_bare("( 1 ( ( ) shift 2 throw ) ( 9 ) try ) reset resume"),
+            Ok ("1 2 9".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("5 ( 1 ( drop ( ) shift drop 2 throw ) ( 9 ) try ) reset resume"),
+            Ok ("5 2 9".to_string())
+        );
+    }
+
+    #[test]
+    fn errors_without_a_try_are_returned() {
+        assert_eq!(evaluate_bare("1 2 3 throw"), Err ("3".to_string()));
+    }
+
+    #[test]
+    fn shift_captures_the_computation_up_to_its_reset() {
+        assert_eq!(evaluate_bare("( 1 ( ) shift 2 ) reset"), Ok ("continuation".to_string()));
+        assert_eq!(evaluate_bare("( 1 ( 10 ) shift 2 ) reset"), Ok ("continuation 10".to_string()));
+        assert_eq!(evaluate_bare("1 ( 2 ( drop ) shift 3 ) reset"), Ok ("1".to_string()));
+    }
+
+    #[test]
+    fn resume_restores_captured_data_and_continues() {
+        assert_eq!(evaluate_bare("( 1 ( ) shift 2 ) reset resume"), Ok ("1 2".to_string()));
+        assert_eq!(
+            evaluate_bare("( 1 ( ) shift 2 + ) reset 5 swap resume"),
+            Ok ("5 3".to_string())
+        );
+    }
+
+    #[test]
+    fn continuations_can_be_resumed_more_than_once() {
+        assert_eq!(
+            evaluate_bare("( ( ) shift 1 + ) reset copy 10 swap resume swap 20 swap resume"),
+            Ok ("11 21".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("( [ 1 2 3 ] ( ( ) shift drop ) each ) reset resume resume resume"),
+            Ok ("".to_string())
+        );
+    }
+
+    #[test]
+    fn shift_can_exit_a_loop_early() {
+        let find: &str = "( 3 = ( ( drop true ) shift ) ( ) ? ) each false ) reset";
+        assert_eq!(evaluate_bare(&format!("( [ 1 2 3 4 ] {}", find)), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare(&format!("( [ 1 2 4 ] {}", find)), Ok ("false".to_string()));
+    }
+
+    #[test]
+    fn continuations_fail_outside_of_a_reset() {
+        assert_eq!(
+            evaluate_bare("( ) shift"),
+            Err ("Cannot perform `shift` outside of a `reset`".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 resume"),
+            Err ("Cannot perform `resume` unless there is a continuation on top of the stack"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn map_literals_are_ordered_by_key() {
+        assert_eq!(evaluate_bare("{ 3 4 1 2 }"), Ok ("{ 1 2 3 4 }".to_string()));
+        assert_eq!(evaluate_bare("{ }"), Ok ("{ }".to_string()));
+        assert_eq!(evaluate_bare("{ ( 1 ) 2 ( 1 ) 3 }"), Ok ("{ ( 1 ) 3 }".to_string()));
+    }
+
+    #[test]
+    fn get_put_and_remove_work_with_keys() {
+        assert_eq!(evaluate_bare("{ 1 2 3 4 } 3 get"), Ok ("4".to_string()));
+        assert_eq!(evaluate_bare("{ 1 2 } 5 6 put"), Ok ("{ 1 2 5 6 }".to_string()));
+        assert_eq!(evaluate_bare("{ 1 2 } 1 9 put"), Ok ("{ 1 9 }".to_string()));
+        assert_eq!(evaluate_bare("{ 1 2 3 4 } 1 remove"), Ok ("{ 3 4 }".to_string()));
+        assert_eq!(evaluate_bare("{ 1 2 } 5 remove"), Ok ("{ 1 2 }".to_string()));
+        assert_eq!(
+            evaluate_bare("{ 1 2 } 5 get"),
+            Err ("Cannot perform `get` with a key that is not in the map".to_string())
+        );
+    }
+
+    #[test]
+    fn has_tests_whether_a_map_contains_a_key() {
+        assert_eq!(evaluate_bare("{ 1 2 } 1 has? { 1 2 } 2 has?"), Ok ("true false".to_string()));
+    }
+
+    #[test]
+    fn keys_values_and_entries_are_ordered_by_key() {
+        assert_eq!(evaluate_bare("{ 2 20 1 10 } keys"), Ok ("[ 1 2 ]".to_string()));
+        assert_eq!(evaluate_bare("{ 2 20 1 10 } values"), Ok ("[ 10 20 ]".to_string()));
+        assert_eq!(
+            evaluate_bare("{ 2 20 1 10 } entries"),
+            Ok ("[ [ 1 10 ] [ 2 20 ] ]".to_string())
+        );
+    }
+
+    #[test]
+    fn map_values_applies_a_function_to_each_value() {
+        assert_eq!(
+            evaluate_bare("{ 1 2 3 4 } ( 10 * ) map-values"),
+            Ok ("{ 1 20 3 40 }".to_string())
+        );
+    }
+
+    #[test]
+    fn compare_orders_two_values() {
+        assert_eq!(evaluate_bare("1 2 compare 2 1 compare 2 2 compare"), Ok ("-1 1 0".to_string()));
+    }
+
+    #[test]
+    fn values_are_ordered_by_kind_and_then_by_contents() {
+        assert_eq!(
+            evaluate_bare("[ true 1 [ 1 ] ( 1 ) false 2/3 1.5e0 ] sort"),
+            Ok ("[ false true 2/3 1 1.5e0 ( 1 ) [ 1 ] ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ [ 1 2 ] [ 1 ] [ 0 5 ] ] sort"),
+            Ok ("[ [ 0 5 ] [ 1 ] [ 1 2 ] ]".to_string())
+        );
+    }
+
+    #[test]
+    fn sort_by_is_stable() {
+        assert_eq!(
+            evaluate_bare("[ [ 2 1 ] [ 1 1 ] [ 2 0 ] [ 1 0 ] ] ( first ) sort-by"),
+            Ok ("[ [ 1 1 ] [ 1 0 ] [ 2 1 ] [ 2 0 ] ]".to_string())
+        );
+    }
+
+    #[test]
+    fn min_and_max_need_a_non_empty_list() {
+        assert_eq!(evaluate_bare("[ 3 1 2 ] min [ 3 1 2 ] max"), Ok ("1 3".to_string()));
+        assert_eq!(evaluate_bare("3 5 2 pack max"), Ok ("5".to_string()));
+        assert_eq!(
+            evaluate_bare("[ ] min"),
+            Err ("Cannot perform `min` on an empty list".to_string())
+        );
+    }
+
+    #[test]
+    fn unique_keeps_the_first_of_equal_items() {
+        assert_eq!(evaluate_bare("[ 3 1 3 2 1 ] unique"), Ok ("[ 3 1 2 ]".to_string()));
+    }
+
+    #[test]
+    fn division_is_exact() {
+        assert_eq!(evaluate_bare("1 3 /"), Ok ("1/3".to_string()));
+        assert_eq!(evaluate_bare("1 3 / 1 3 / + 2/3 ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("3/4 1/4 +"), Ok ("1".to_string()));
+        assert_eq!(
+            evaluate_bare("1 0 /"),
+            Err ("Cannot perform `/` with a divisor of zero".to_string())
+        );
+    }
+
+    #[test]
+    fn integers_are_promoted_to_rationals() {
+        assert_eq!(evaluate_bare("1/3 1 +"), Ok ("4/3".to_string()));
+        assert_eq!(evaluate_bare("7/2 2 %"), Ok ("3/2".to_string()));
+        assert_eq!(evaluate_bare("1 2/3 < 1 4/3 <"), Ok ("false true".to_string()));
+        assert_eq!(evaluate_bare("2/2 1 ="), Ok ("true".to_string()));
+    }
+
+    #[test]
+    fn rationals_are_written_as_decimals_or_fractions() {
+        assert_eq!(evaluate_bare("1.25 6/4"), Ok ("5/4 3/2".to_string()));
+    }
+
+    #[test]
+    fn rationals_are_rounded_and_taken_apart() {
+        assert_eq!(
+            evaluate_bare("7/2 floor 7/2 ceil 7/2 round -7/2 floor -7/2 round"),
+            Ok ("3 4 4 -4 -4".to_string())
+        );
+        assert_eq!(evaluate_bare("3/4 numerator 3/4 denominator"), Ok ("3 4".to_string()));
+        assert_eq!(evaluate_bare("5 numerator 5 denominator"), Ok ("5 1".to_string()));
+    }
+
+    #[test]
+    fn floats_support_approximate_arithmetic() {
+        assert_eq!(evaluate_bare("1.5e0 2e0 +"), Ok ("3.5e0".to_string()));
+        assert_eq!(evaluate_bare("2e0 3e0 pow 9e0 sqrt"), Ok ("8e0 3e0".to_string()));
+        assert_eq!(evaluate_bare("0e0 exp 1e0 log"), Ok ("1e0 0e0".to_string()));
+        assert_eq!(evaluate_bare("0e0 sin 0e0 cos"), Ok ("0e0 1e0".to_string()));
+        assert_eq!(evaluate_bare("1e0 0e0 /"), Ok ("inf".to_string()));
+    }
+
+    #[test]
+    fn floats_are_not_mixed_with_exact_numbers() {
+        assert_eq!(
+            evaluate_bare("1.5e0 1 +"),
+            Err ("Cannot perform arithmetic operation on a float and an exact number without \
+            converting one of them with `int>float` or `float>int`".to_string())
+        );
+    }
+
+    #[test]
+    fn floats_are_converted_explicitly() {
+        assert_eq!(evaluate_bare("1 int>float"), Ok ("1e0".to_string()));
+        assert_eq!(evaluate_bare("2.7e0 float>int -2.7e0 float>int"), Ok ("2 -2".to_string()));
+    }
+
+    #[test]
+    fn nan_compares_predictably() {
+        assert_eq!(evaluate_bare("0e0 0e0 / copy ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("0e0 0e0 / 1e0 >"), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("0e0 -0e0 ="), Ok ("true".to_string()));
+    }
+
+    #[test]
+    fn integer_division_can_be_floored_or_truncated() {
+        assert_eq!(evaluate_bare("-7 2 div -7 2 mod"), Ok ("-4 1".to_string()));
+        assert_eq!(evaluate_bare("-7 2 divmod"), Ok ("-4 1".to_string()));
+        assert_eq!(evaluate_bare("-7 2 quot -7 2 %"), Ok ("-3 -1".to_string()));
+        assert_eq!(
+            evaluate_bare("1 0 div"),
+            Err ("Cannot perform `div` with a divisor of zero".to_string())
+        );
+    }
+
+    #[test]
+    fn integers_are_raised_to_powers() {
+        assert_eq!(evaluate_bare("2 10 pow 2 -1 pow"), Ok ("1024 1/2".to_string()));
+        assert_eq!(evaluate_bare("3 200 7 powmod"), Ok ("2".to_string()));
+        assert_eq!(
+            evaluate_bare("2 100000000000 pow"),
+            Err ("Cannot perform `pow` because the result would be too large".to_string())
+        );
+    }
+
+    #[test]
+    fn integer_signs_and_divisors_are_computed() {
+        assert_eq!(evaluate_bare("-5 abs 5 negate -5 sign 0 sign"), Ok ("5 -5 -1 0".to_string()));
+        assert_eq!(evaluate_bare("12 18 gcd 4 6 lcm"), Ok ("6 12".to_string()));
+        assert_eq!(evaluate_bare("17 isqrt 16 isqrt"), Ok ("4 4".to_string()));
+        assert_eq!(
+            evaluate_bare("-1 isqrt"),
+            Err ("Cannot perform `isqrt` on a negative integer".to_string())
+        );
+    }
+
+    #[test]
+    fn integer_bits_are_manipulated() {
+        assert_eq!(
+            evaluate_bare("12 10 bit-and 12 10 bit-or 12 10 bit-xor 0 bit-not"),
+            Ok ("8 14 6 -1".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 10 shift-left 1024 3 shift-right -1 1 shift-right"),
+            Ok ("1024 128 -1".to_string())
+        );
+        assert_eq!(evaluate_bare("255 bit-length 0 bit-length"), Ok ("8 0".to_string()));
+    }
+
+    #[test]
+    fn bytes_are_indexed_sliced_and_joined() {
+        assert_eq!(evaluate_bare("#[ 01 02 ff ] 1 index"), Ok ("2".to_string()));
+        assert_eq!(evaluate_bare("#[ 01 02 ff ] length"), Ok ("3".to_string()));
+        assert_eq!(evaluate_bare("#[ 01 02 ff ] 1 3 slice"), Ok ("#[ 02 ff ]".to_string()));
+        assert_eq!(evaluate_bare("#[ 01 02 ] #[ 03 ] join"), Ok ("#[ 01 02 03 ]".to_string()));
+    }
+
+    #[test]
+    fn bytes_are_converted_to_and_from_lists() {
+        assert_eq!(evaluate_bare("#[ 01 02 ff ] bytes>list"), Ok ("[ 1 2 255 ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 255 ] list>bytes"), Ok ("#[ 01 02 ff ]".to_string()));
+        assert_eq!(
+            evaluate_bare("[ 256 ] list>bytes"),
+            Err ("Cannot perform `list>bytes` unless every item in the list is an integer from 0 \
+            to 255".to_string())
+        );
+    }
+
+    #[test]
+    fn integers_are_encoded_with_a_width_and_byte_order() {
+        assert_eq!(
+            evaluate_bare("258 2 encode-be 258 2 encode-le"),
+            Ok ("#[ 01 02 ] #[ 02 01 ]".to_string())
+        );
+        assert_eq!(evaluate_bare("1 3 encode-be"), Ok ("#[ 00 00 01 ]".to_string()));
+        assert_eq!(
+            evaluate_bare("#[ 01 02 ] decode-be #[ 01 02 ] decode-le"),
+            Ok ("258 513".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("256 1 encode-be"),
+            Err ("Cannot perform `encode-be` unless the integer is non-negative and fits in the \
+            width".to_string())
+        );
+    }
+
+    #[test]
+    fn bytes_are_encoded_as_text() {
+        assert_eq!(evaluate_bare("#[ de ad ] bytes>hex"), Ok ("#[ 64 65 61 64 ]".to_string()));
+        assert_eq!(evaluate_bare("#[ 64 65 61 64 ] hex>bytes"), Ok ("#[ de ad ]".to_string()));
+        assert_eq!(
+            evaluate_bare("#[ 66 6f 6f ] bytes>base64"),
+            Ok ("#[ 5a 6d 39 76 ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("#[ 5a ] base64>bytes"),
+            Err ("Cannot perform `base64>bytes` on malformed bytes".to_string())
+        );
+    }
+
+
+    fn option() -> Namespace {
+        let mut namespace: Namespace = Namespace::new();
+        define(&mut namespace, "union option none some(x) ;");
+        namespace
+    }
+
+    #[test]
+    fn union_variants_are_constructed_and_tested() {
+        let mut namespace: Namespace = option();
+        assert_eq!(
+            evaluate_in(&mut namespace, "none 5 some"),
+            Ok ("none some { x: 5 }".to_string())
+        );
+        assert_eq!(evaluate_in(&mut namespace, "5 some some_x"), Ok ("5".to_string()));
+        assert_eq!(
+            evaluate_in(&mut namespace, "none is_none none is_some"),
+            Ok ("true false".to_string())
+        );
+    }
+
+    #[test]
+    fn match_pushes_fields_and_applies_the_lambda_for_the_variant() {
+        let mut namespace: Namespace = option();
+        assert_eq!(
+            evaluate_in(&mut namespace, "5 some ( 0 ) ( 1 + ) match"),
+            Ok ("6".to_string())
+        );
+        assert_eq!(evaluate_in(&mut namespace, "none ( 0 ) ( 1 + ) match"), Ok ("0".to_string()));
+        define(&mut namespace, "union shape circle(r) rectangle(w h) ;");
+        assert_eq!(
+            evaluate_in(&mut namespace, "2 3 rectangle ( copy * ) ( * ) match"),
+            Ok ("6".to_string())
+        );
+    }
+
+    #[test]
+    fn match_fails_without_a_case_for_every_variant() {
+        let mut namespace: Namespace = option();
+        assert_eq!(
+            evaluate_in(&mut namespace, "5 some ( 1 + ) match"),
+            Err ("Cannot perform `match` on a value of the union `option` unless there is one \
+            lambda above it for each of its variants: none, some".to_string())
+        );
+        define(&mut namespace, "record point x y ;");
+        assert_eq!(
+            evaluate_in(&mut namespace, "1 2 point ( ) match"),
+            Err ("Cannot perform `match` on a record that is not a variant of a union".to_string())
+        );
+        assert_eq!(
+            evaluate_in(&mut namespace, "1 ( ) match"),
+            Err ("Cannot perform `match` unless there is a union value below a lambda for each of \
+            its variants".to_string())
+        );
+    }
+
+
+    #[test]
+    fn cond_applies_the_action_of_the_first_predicate_that_holds() {
+        let clauses: &str = "[ [ ( 0 < ) ( drop -1 ) ] [ ( 0 = ) ( drop 0 ) ] [ ( 0 = ) ( ) ] ]";
+        let evaluate =
+            |value: i32| evaluate_bare(&format!("{} {} ( drop 1 ) cond", value, clauses));
+        assert_eq!(evaluate(-5), Ok ("-1".to_string()));
+        assert_eq!(evaluate(0), Ok ("0".to_string()));
+        assert_eq!(evaluate(5), Ok ("1".to_string()));
+        assert_eq!(evaluate_bare("5 [ ] ( 1 + ) cond"), Ok ("6".to_string()));
+    }
+
+    #[test]
+    fn cond_fails_with_malformed_clauses_or_predicates() {
+        assert_eq!(
+            evaluate_bare("5 [ [ ( 0 < ) ] ] ( ) cond"),
+            Err ("Cannot perform `cond` unless every clause is a list of a predicate lambda and an \
+            action lambda".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("5 [ [ ( 1 ) ( ) ] ] ( ) cond"),
+            Err ("Predicate of a `cond` clause must evaluate to a boolean".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ [ ( true ) ( ) ] ] ( ) cond"),
+            Err ("Cannot test `cond` clauses on an empty stack".to_string())
+        );
+    }
+
+    #[test]
+    fn list_and_map_literals_can_be_nested() {
+        assert_eq!(
+            evaluate_bare("[ [ 1 2 ] [ ] [ [ 3 ] ] ]"),
+            Ok ("[ [ 1 2 ] [ ] [ [ 3 ] ] ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("{ 1 [ 2 3 ] 0 { 4 5 } }"),
+            Ok ("{ 0 { 4 5 } 1 [ 2 3 ] }".to_string())
+        );
+        assert_eq!(evaluate_bare("[ { 1 [ 2 ] } ]"), Ok ("[ { 1 [ 2 ] } ]".to_string()));
+    }
+
+
+    #[test]
+    fn lists_are_taken_apart_at_either_end() {
+        assert_eq!(evaluate_bare("[ 1 2 3 ] uncons"), Ok ("1 [ 2 3 ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] first [ 1 2 3 ] last"), Ok ("1 3".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] rest [ 1 ] rest"), Ok ("[ 2 3 ] [ ]".to_string()));
+        for name in ["uncons", "first", "last", "rest"] {
+            assert_eq!(
+                evaluate_bare(&format!("[ ] {}", name)),
+                Err (format!("Cannot perform `{}` on an empty list", name))
+            );
+        }
+    }
+
+    #[test]
+    fn pack_and_unpack_move_items_between_lists_and_the_stack() {
+        assert_eq!(evaluate_bare("[ 1 2 3 ] unpack"), Ok ("1 2 3".to_string()));
+        assert_eq!(evaluate_bare("[ ] unpack"), Ok ("".to_string()));
+        assert_eq!(evaluate_bare("0 1 2 3 2 pack"), Ok ("0 1 [ 2 3 ]".to_string()));
+        assert_eq!(evaluate_bare("0 pack"), Ok ("[ ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] unpack 3 pack"), Ok ("[ 1 2 3 ]".to_string()));
+        assert_eq!(
+            evaluate_bare("1 2 3 pack"),
+            Err ("Cannot perform `pack` unless there are as many items below the count as it asks \
+            for".to_string())
+        );
+        assert!(evaluate_bare("1 -1 pack").is_err());
+    }
+
+    #[test]
+    fn split_at_clamps_and_counts_negative_indices_from_the_end() {
+        assert_eq!(evaluate_bare("[ 1 2 3 ] 1 split-at"), Ok ("[ 1 ] [ 2 3 ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] -1 split-at"), Ok ("[ 1 2 ] [ 3 ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] 5 split-at"), Ok ("[ 1 2 3 ] [ ]".to_string()));
+        assert_eq!(evaluate_bare("[ 1 2 3 ] -5 split-at"), Ok ("[ ] [ 1 2 3 ]".to_string()));
+    }
+
+    #[test]
+    fn lists_are_reversed_zipped_enumerated_and_flattened() {
+        assert_eq!(
+            evaluate_bare("[ 1 2 3 ] reverse [ ] reverse"),
+            Ok ("[ 3 2 1 ] [ ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ 1 2 3 ] [ 4 5 ] zip"),
+            Ok ("[ [ 1 4 ] [ 2 5 ] ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ 5 6 ] enumerate"),
+            Ok ("[ [ 0 5 ] [ 1 6 ] ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("[ [ 1 2 ] 3 [ ] [ [ 4 ] ] ] flatten"),
+            Ok ("[ 1 2 3 [ 4 ] ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("1 reverse"),
+            Err ("Cannot perform `reverse` unless there is a list on top of the stack".to_string())
+        );
+    }
+
+
+    #[test]
+    fn ranges_stop_before_their_end() {
+        assert_eq!(evaluate_bare("1 4 range"), Ok ("[ 1 2 3 ]".to_string()));
+        assert_eq!(evaluate_bare("-2 1 range"), Ok ("[ -2 -1 0 ]".to_string()));
+        assert_eq!(evaluate_bare("3 3 range"), Ok ("[ ]".to_string()));
+        assert_eq!(evaluate_bare("5 1 range"), Ok ("[ ]".to_string()));
+        assert_eq!(
+            evaluate_bare("4 iota 0 iota -3 iota"),
+            Ok ("[ 0 1 2 3 ] [ ] [ ]".to_string())
+        );
+    }
+
+    #[test]
+    fn range_steps_can_be_negative_but_not_zero() {
+        assert_eq!(evaluate_bare("0 10 3 range-step"), Ok ("[ 0 3 6 9 ]".to_string()));
+        assert_eq!(evaluate_bare("0 9 3 range-step"), Ok ("[ 0 3 6 ]".to_string()));
+        assert_eq!(evaluate_bare("10 0 -4 range-step"), Ok ("[ 10 6 2 ]".to_string()));
+        assert_eq!(evaluate_bare("0 10 -1 range-step"), Ok ("[ ]".to_string()));
+        assert_eq!(evaluate_bare("10 0 1 range-step"), Ok ("[ ]".to_string()));
+        assert_eq!(evaluate_bare("3 3 -1 range-step"), Ok ("[ ]".to_string()));
+        assert_eq!(
+            evaluate_bare("0 10 0 range-step"),
+            Err ("Cannot perform `range-step` with a step of zero".to_string())
+        );
+    }
+
+    #[test]
+    fn repeat_makes_a_list_of_copies() {
+        assert_eq!(evaluate_bare("[ 1 ] 3 repeat"), Ok ("[ [ 1 ] [ 1 ] [ 1 ] ]".to_string()));
+        assert_eq!(evaluate_bare("1 0 repeat 1 -2 repeat"), Ok ("[ ] [ ]".to_string()));
+    }
+
+    #[test]
+    fn generated_lists_are_limited_in_length() {
+        let limit: usize = MAXIMUM_GENERATED_ITEMS;
+        assert_eq!(generated_length(&Integer::from_usize(limit)), Some (limit));
+        assert_eq!(generated_length(&Integer::from_usize(limit + 1)), None);
+        assert_eq!(generated_length(&(Integer::from_usize(0) - Integer::from_usize(1))), Some (0));
+        let error = |name: &str| Err (format!("Cannot perform `{}` to make a list of more than {} \
+            items", name, limit));
+        assert_eq!(evaluate_bare(&format!("0 {} range", limit + 1)), error("range"));
+        assert_eq!(evaluate_bare(&format!("-1 {} range", limit)), error("range"));
+        assert_eq!(evaluate_bare(&format!("{} iota", limit + 1)), error("iota"));
+        assert_eq!(
+            evaluate_bare(&format!("0 {} 2 range-step", 2 * limit + 1)),
+            error("range-step")
+        );
+        assert_eq!(evaluate_bare(&format!("1 {} repeat", limit + 1)), error("repeat"));
+    }
+
+    #[test]
+    fn count_counts_the_items_a_predicate_holds_for() {
+        assert_eq!(evaluate_bare("[ 1 2 3 4 5 ] ( 2 % 0 = ) count"), Ok ("2".to_string()));
+        assert_eq!(evaluate_bare("[ ] ( drop true ) count"), Ok ("0".to_string()));
+        assert_eq!(evaluate_bare("100000 iota ( -1 > ) count"), Ok ("100000".to_string()));
+        assert_eq!(
+            evaluate_bare("[ 1 ] ( ) count"),
+            Err ("Cannot perform `count` unless its predicate evaluates to a boolean".to_string())
+        );
+    }
+
+
+    #[test]
+    fn dip_and_under_apply_a_function_below_the_top_value() {
+        assert_eq!(evaluate_bare("1 2 3 ( + ) dip"), Ok ("3 3".to_string()));
+        assert_eq!(evaluate_bare("1 2 3 ( + ) under"), Ok ("3 3".to_string()));
+        assert_eq!(evaluate_bare("1 2 ( drop ) dip"), Ok ("2".to_string()));
+        assert_eq!(
+            evaluate_bare("( ) under"),
+            Err ("Cannot perform `under` unless there is a lambda above a value on top of the \
+            stack".to_string())
+        );
+    }
+
+    #[test]
+    fn keep_applies_a_function_and_restores_its_argument() {
+        assert_eq!(evaluate_bare("5 ( 1 + ) keep"), Ok ("6 5".to_string()));
+        assert_eq!(evaluate_bare("2 5 ( * ) keep"), Ok ("10 5".to_string()));
+        assert_eq!(
+            evaluate_bare("( ) keep"),
+            Err ("Cannot perform `keep` unless there is a lambda above a value on top of the \
+            stack".to_string())
+        );
+    }
+
+    #[test]
+    fn bi_tri_and_cleave_apply_functions_to_the_same_value() {
+        assert_eq!(evaluate_bare("5 ( 1 + ) ( 2 * ) bi"), Ok ("6 10".to_string()));
+        assert_eq!(evaluate_bare("5 ( 1 + ) ( 2 * ) ( ) tri"), Ok ("6 10 5".to_string()));
+        assert_eq!(
+            evaluate_bare("5 [ ( 1 + ) ( 2 * ) ( copy * ) ] cleave"),
+            Ok ("6 10 25".to_string())
+        );
+        assert_eq!(evaluate_bare("5 [ ] cleave"), Ok ("".to_string()));
+        assert_eq!(
+            evaluate_bare("5 [ 1 ] cleave"),
+            Err ("Cannot perform `cleave` unless there is a list of lambdas above a value on top \
+            of the stack".to_string())
+        );
+    }
+
+    #[test]
+    fn spread_applies_each_function_to_its_own_value() {
+        assert_eq!(evaluate_bare("1 2 [ ( 10 + ) ( 20 * ) ] spread"), Ok ("11 40".to_string()));
+        assert_eq!(evaluate_bare("1 [ ] spread"), Ok ("1".to_string()));
+        assert_eq!(
+            evaluate_bare("1 [ ( ) ( ) ] spread"),
+            Err ("Cannot perform `spread` unless there is a value below the list for each of its \
+            lambdas".to_string())
+        );
+    }
+
+    #[test]
+    fn dataflow_combinators_can_be_captured_by_shift() {
+        assert_eq!(
+            evaluate_bare("( 5 ( drop ( ) shift 7 ) ( 2 * ) bi ) reset"),
+            Ok ("continuation".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("( 5 ( drop ( ) shift 7 ) ( 2 * ) bi ) reset resume"),
+            Ok ("7 10".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("( 1 2 [ ( drop ( ) shift ) ( 1 + ) ] spread ) reset resume"),
+            Ok ("3".to_string())
+        );
+    }
+
+
+
+    #[test]
+    fn lambdas_with_equal_bodies_are_equal() {
+        assert_eq!(evaluate_bare("( 1 + ) ( 1 + ) ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("( 1 + ) ( 2 + ) ="), Ok ("false".to_string()));
+        assert_eq!(evaluate_bare("( 1 2 ) ( 1 ) ( 2 ) compose ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("( 1 ) ( 2 ) compose ( 1 2 ) ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("( 1 + ) 2 swap curry ( 2 1 + ) ="), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("( ) ( ) ( ) compose ="), Ok ("true".to_string()));
+        assert_eq!(
+            evaluate_bare("( 1 2 ) 1 pack ( 1 ) ( 2 ) compose 1 pack ="),
+            Ok ("true".to_string())
+        );
+    }
+
+    #[test]
+    fn lambdas_that_differ_only_in_the_names_of_their_locals_are_equal() {
+        assert_eq!(
+            evaluate_bare("3 [n] -> ( ( n + ) ) 3 [m] -> ( ( m + ) ) ="),
+            Ok ("true".to_string())
+        );
+        assert_eq!(evaluate_bare("( [a] -> ( a ) ) ( [b] -> ( b ) ) ="), Ok ("true".to_string()));
+        assert_eq!(
+            evaluate_bare("3 [n] -> ( ( n + ) ) 4 [m] -> ( ( m + ) ) ="),
+            Ok ("false".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("( [a b] -> ( a ) ) ( [a b] -> ( b ) ) ="),
+            Ok ("false".to_string())
+        );
+    }
+
+    #[test]
+    fn lambdas_are_only_identical_to_their_copies() {
+        assert_eq!(evaluate_bare("( 1 + ) copy identical?"), Ok ("true".to_string()));
+        assert_eq!(evaluate_bare("( 1 + ) ( 1 + ) identical?"), Ok ("false".to_string()));
+        assert_eq!(
+            evaluate_bare("( 1 2 ) ( 1 ) ( 2 ) compose identical?"),
+            Ok ("false".to_string())
+        );
+        assert_eq!(evaluate_bare("1 1 identical?"), Ok ("true".to_string()));
+    }
+
+    #[test]
+    fn composed_and_curried_lambdas_evaluate_their_whole_body() {
+        assert_eq!(evaluate_bare("( 1 ) ( 2 + ) compose apply"), Ok ("3".to_string()));
+        assert_eq!(
+            evaluate_bare("5 ( 1 + ) curry ( 2 * ) compose apply"),
+            Ok ("12".to_string())
+        );
+    }
+
+}
@@ -3,11 +3,29 @@
 use crate::{
     Environment,
     Function,
+    LambdaReference,
     Term,
     Value,
 };
 use smallvec::SmallVec;
-use super::DataStack;
+use std::{
+    cmp::Ordering,
+    fmt::{
+        Debug,
+        Formatter,
+        self,
+    },
+    iter::Zip,
+    mem,
+    sync::Arc,
+    vec::IntoIter,
+};
+use super::{
+    DataStack,
+    Pull,
+    Sequence,
+    bytecode::execute,
+};
 
 
 /// Describes how the `VirtualMachine` should manipulate its `ControlStack` after an
@@ -17,6 +35,9 @@ pub enum ControlAction<'vm> {
     Continue,
     /// Halts evaluation, and returns an error
     Error (String),
+    /// Pushes a `ControlFrame`, such as a loop or an error handler, to the `ControlStack` before
+    /// continuing evaluation
+    PushFrame (ControlFrame<'vm>),
     // /// Folds a list into one value
     // FoldList {
     //     start: Value,
@@ -35,22 +56,282 @@ pub enum ControlAction<'vm> {
     Pop,
     /// Pushes a new `ControlFrame` to the `ControlStack` before continuing evaluation
     Push (Function<'vm>),
+    /// Raises a value as an error, unwinding the `ControlStack` to the nearest error handler
+    Raise (Value),
+    /// Resumes a `Continuation` on top of the `ControlStack`
+    Resume (Continuation),
+    /// Captures the `ControlStack` up to the nearest `reset` as a `Continuation`, and applies a
+    /// `Function` to it
+    Shift (Function<'vm>),
+}
+
+
+
+/// A delimited continuation captured by `shift`, made up of the `ControlFrame`s and the data that
+/// were added to the stacks after the nearest `reset`
+#[derive(Clone)]
+pub struct Continuation (Arc<(Vec<ControlFrame<'static>>, Vec<Value>)>);
+
+impl Debug for Continuation {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str("Continuation") }
+
+}
+
+impl Eq for Continuation { }
+
+impl Ord for Continuation {
+
+    fn cmp(&self, other: &Self) -> Ordering { Arc::as_ptr(&self.0).cmp(&Arc::as_ptr(&other.0)) }
+
+}
+
+impl PartialEq for Continuation {
+
+    fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.0, &other.0) }
+
+}
+
+impl PartialOrd for Continuation {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+
 }
 
 
 
-/// Represents a function being executed
-pub struct ControlFrame<'vm> {
-    /// The `Function` that was applied to create this `ControlFrame`
-    function: Function<'vm>,
-    /// The index of the next term to be evaluated in the `Function`
-    index: usize,
+/// Represents a function being executed, or a loop that repeatedly applies functions
+#[derive(Clone)]
+pub enum ControlFrame<'vm> {
+
+    /// Applies a number of lambdas in order, each to a copy of the same value
+    Cleave {
+        /// The lambdas that have not been applied yet
+        functions: IntoIter<LambdaReference>,
+        /// The value that each lambda is applied to
+        value: Value,
+    },
+
+    /// Applies the action of the first clause whose predicate holds for a copy of the value on top
+    /// of the stack, or a default lambda if none of them hold
+    Cond {
+        /// The action of the clause whose predicate is being tested
+        action: Option<LambdaReference>,
+        /// The clauses that have not been tested yet, as pairs of predicate and action lambdas
+        clauses: IntoIter<(LambdaReference, LambdaReference)>,
+        /// The lambda applied if no predicate holds, until a lambda has been applied
+        default: Option<LambdaReference>,
+    },
+
+    /// Evaluates the terms of a `Function` in order
+    Evaluation {
+        /// The `Function` that was applied to create this `ControlFrame`
+        function: Function<'vm>,
+        /// The index of the next term to be evaluated in the `Function`
+        index: usize,
+        /// The values bound to local names in this `ControlFrame`, indexed by slot
+        locals: Vec<Value>,
+    },
+
+    /// Executes the `Instruction`s that the body of a function stored in an `Environment` was
+    /// lowered into
+    Execution {
+        /// The index of the next `Instruction` to be executed
+        counter: usize,
+        /// The index after the last `Instruction` of the function
+        end: usize,
+        /// The values bound to local names in this `ControlFrame`, indexed by slot
+        locals: Vec<Value>,
+        /// The index of the first `Instruction` of the function
+        start: usize,
+    },
+
+    /// Applies a `Function` to each item in a list
+    Each {
+        /// The `Function` applied to each item
+        function: Function<'vm>,
+        /// The items that the `Function` has not been applied to yet
+        items: IntoIter<Value>,
+    },
+
+    /// Makes a list of the items of a list for which a predicate `Function` holds
+    Filter {
+        /// The `Function` applied to each item to decide whether to keep it
+        function: Function<'vm>,
+        /// The items that have not been tested yet
+        items: IntoIter<Value>,
+        /// The items that have been kept
+        kept: Vec<Value>,
+        /// The item being tested
+        pending: Option<Value>,
+    },
+
+    /// Collects the items of a `Sequence` into a list
+    Force {
+        /// The items collected so far
+        items: Vec<Value>,
+        /// The rest of the `Sequence`, or `None` while an item is being pulled from it
+        sequence: Option<Sequence>,
+    },
+
+    /// Makes a list of the results of applying a `Function` to each item in a list
+    Map {
+        /// The `Function` applied to each item
+        function: Function<'vm>,
+        /// The items that the `Function` has not been applied to yet
+        items: IntoIter<Value>,
+        /// The results of the applications so far
+        mapped: Vec<Value>,
+        /// Whether the `Function` has been applied to an item, and its result must be collected
+        pending: bool,
+    },
+
+    /// Pulls the next item from a `Sequence`
+    Next {
+        /// The `Sequence` that the item is pulled from
+        sequence: Sequence,
+        /// How far pulling the item has progressed
+        state: Pull,
+    },
+
+    /// Delimits the `ControlFrame`s captured by `shift`, after applying a body `Function`
+    Reset {
+        /// The `Function` applied inside the `reset`, until it has been applied
+        body: Option<Function<'vm>>,
+        /// The size of the stack when the `reset` was entered
+        depth: usize,
+    },
+
+    /// Applies a number of lambdas in order, each to the value paired with it
+    Spread {
+        /// The values paired with the lambdas applied to them, which have not been applied yet
+        applications: Zip<IntoIter<Value>, IntoIter<LambdaReference>>,
+    },
+
+    /// Sorts a list by the keys made by applying a `Function` to each of its items
+    SortBy {
+        /// The `Function` that makes the key of each item
+        function: Function<'vm>,
+        /// The items that have been paired with their keys
+        keyed: Vec<(Value, Value)>,
+        /// The items that have not been paired with their keys yet
+        items: IntoIter<Value>,
+        /// The item whose key is being made
+        pending: Option<Value>,
+    },
+
+    /// Applies a `Function` a number of times
+    Times {
+        /// The `Function` that is applied
+        function: Function<'vm>,
+        /// The number of applications remaining
+        remaining: usize,
+    },
+
+    /// Applies a body `Function`, and applies a handler `Function` to the error if it fails
+    Try {
+        /// The `Function` applied inside the `try`, until it has been applied
+        body: Option<Function<'vm>>,
+        /// The size of the stack when the `try` was entered
+        depth: usize,
+        /// The `Function` applied to the error if the body fails
+        handler: Function<'vm>,
+    },
+
+    /// Applies a body `Function` for as long as a condition `Function` evaluates to an expected
+    /// boolean
+    While {
+        /// The `Function` applied on each iteration
+        body: Function<'vm>,
+        /// The `Function` applied before each iteration to decide whether to continue
+        condition: Function<'vm>,
+        /// The boolean that the condition must evaluate to for the loop to continue
+        expected: bool,
+        /// Whether the condition has been applied, and its result must be tested
+        testing: bool,
+    },
+
 }
 
 impl<'a> ControlFrame<'a> {
 
-    /// Creates a `ControlFrame` from `Term`s
-    pub const fn from_function(function: Function<'a>) -> Self { Self { function, index: 0, } }
+    /// Creates a `ControlFrame` from a `Function`, which executes its `Instruction`s if it is the
+    /// unextended body of a function stored in an `Environment` that executes bytecode, or
+    /// evaluates its `Term`s, so the extended bodies of composed and curried lambdas are always
+    /// evaluated term by term
+    pub fn from_function(function: Function<'a>, environment: &Environment) -> Self {
+        match function {
+            Function::Contiguous (start, body, tail)
+                if tail.is_empty() && environment.executes_bytecode() => Self::Execution {
+                counter: start,
+                end: start + body.len(),
+                locals: Vec::new(),
+                start,
+            },
+            function => Self::Evaluation { function, index: 0, locals: Vec::new() },
+        }
+    }
+
+    /// Returns whether this `ControlFrame` evaluates a function that has no terms left to evaluate,
+    /// so that it can be replaced by a frame pushed after it instead of returning to it
+    pub fn is_finished(&self) -> bool {
+        match self {
+            Self::Evaluation { function, index, .. } => function.term(*index).is_none(),
+            Self::Execution { counter, end, .. } => counter >= end,
+            _ => false,
+        }
+    }
+
+    /// Converts this `ControlFrame` into one that does not borrow from an `Environment`
+    pub fn into_owned(self) -> ControlFrame<'static> {
+        match self {
+            Self::Cleave { functions, value } => ControlFrame::Cleave { functions, value },
+            Self::Cond { action, clauses, default } =>
+                ControlFrame::Cond { action, clauses, default },
+            Self::Evaluation { function, index, locals } =>
+                ControlFrame::Evaluation { function: function.into_owned(), index, locals },
+            Self::Each { function, items } =>
+                ControlFrame::Each { function: function.into_owned(), items },
+            Self::Execution { counter, end, locals, start } =>
+                ControlFrame::Execution { counter, end, locals, start },
+            Self::Filter { function, items, kept, pending } => ControlFrame::Filter {
+                function: function.into_owned(),
+                items,
+                kept,
+                pending
+            },
+            Self::Force { items, sequence } => ControlFrame::Force { items, sequence },
+            Self::Map { function, items, mapped, pending } => ControlFrame::Map {
+                function: function.into_owned(),
+                items,
+                mapped,
+                pending
+            },
+            Self::Next { sequence, state } => ControlFrame::Next { sequence, state },
+            Self::Reset { body, depth } =>
+                ControlFrame::Reset { body: body.map(Function::into_owned), depth },
+            Self::Spread { applications } => ControlFrame::Spread { applications },
+            Self::SortBy { function, keyed, items, pending } => ControlFrame::SortBy {
+                function: function.into_owned(),
+                keyed,
+                items,
+                pending
+            },
+            Self::Times { function, remaining } =>
+                ControlFrame::Times { function: function.into_owned(), remaining },
+            Self::Try { body, depth, handler } => ControlFrame::Try {
+                body: body.map(Function::into_owned),
+                depth,
+                handler: handler.into_owned(),
+            },
+            Self::While { body, condition, expected, testing } => ControlFrame::While {
+                body: body.into_owned(),
+                condition: condition.into_owned(),
+                expected,
+                testing
+            },
+        }
+    }
 
     /// Runs one step in the evaluation process for this `ControlFrame`
     pub fn execute_step(
@@ -58,23 +339,260 @@ impl<'a> ControlFrame<'a> {
         data_stack: &mut DataStack,
         environment: &'a Environment,
     ) -> ControlAction<'a> {
-        let Some (term) = self.function.body().get(self.index) else { return ControlAction::Pop };
-        let action: ControlAction = match term {
-            Term::Application (reference) => {
-                let function: Function = reference.get(environment);
-                ControlAction::Push (function)
-            },
-            Term::Combinator (combinator) => combinator.evaluate(data_stack, environment),
-            Term::Data (data) => {
-                data_stack.push(data.clone());
-                ControlAction::Continue
-            },
-            Term::Recursion => ControlAction::Push (self.function.clone()),
-        };
-        self.index += 1;
-        action
+        match self {
+            Self::Cleave { functions, value } => match functions.next() {
+                Some (function) => {
+                    data_stack.push(value.clone());
+                    ControlAction::Push (function.get(environment))
+                }
+                None => ControlAction::Pop,
+            },
+            Self::Cond { action, clauses, default } => if let Some (tested) = action.take() {
+                match data_stack.pop() {
+                    Some (Value::Boolean (true)) => {
+                        // no other clauses are tested once one is chosen
+                        *clauses = IntoIter::default();
+                        *default = None;
+                        ControlAction::Push (tested.get(environment))
+                    }
+                    Some (Value::Boolean (false)) => ControlAction::Continue,
+                    _ => ControlAction::Error ("Predicate of a `cond` clause must evaluate to a \
+                    boolean".to_string()),
+                }
+            } else if let Some ((predicate, tested)) = clauses.next() {
+                let Some (top) = data_stack.get_from_top(0).cloned() else {
+                    return ControlAction::Error ("Cannot test `cond` clauses on an empty stack"
+                        .to_string())
+                };
+                data_stack.push(top);
+                *action = Some (tested);
+                ControlAction::Push (predicate.get(environment))
+            } else {
+                match default.take() {
+                    Some (default) => ControlAction::Push (default.get(environment)),
+                    None => ControlAction::Pop,
+                }
+            },
+            Self::Evaluation { function, index, locals } =>
+                evaluate_term(function, index, locals, data_stack, environment),
+            Self::Execution { counter, end, locals, start } =>
+                execute(start, end, counter, locals, data_stack, environment),
+            Self::Each { function, items } => match items.next() {
+                Some (item) => {
+                    data_stack.push(item);
+                    ControlAction::Push (function.clone())
+                }
+                None => ControlAction::Pop,
+            },
+            Self::Filter { function, items, kept, pending } => {
+                if let Some (item) = pending.take() {
+                    match data_stack.pop() {
+                        Some (Value::Boolean (true)) => kept.push(item),
+                        Some (Value::Boolean (false)) => {}
+                        _ => return ControlAction::Error ("Predicate of `filter` must evaluate \
+                        to a boolean".to_string()),
+                    }
+                }
+                match items.next() {
+                    Some (item) => {
+                        data_stack.push(item.clone());
+                        *pending = Some (item);
+                        ControlAction::Push (function.clone())
+                    }
+                    None => {
+                        data_stack.push(Value::List (mem::take(kept)));
+                        ControlAction::Pop
+                    }
+                }
+            },
+            Self::Force { items, sequence } => match sequence.take() {
+                Some (sequence) => ControlAction::PushFrame (ControlFrame::Next {
+                    sequence,
+                    state: Pull::Start,
+                }),
+                None => match data_stack.pop() {
+                    Some (Value::Boolean (true)) => match (data_stack.pop(), data_stack.pop()) {
+                        (Some (Value::Sequence (rest)), Some (item)) => {
+                            items.push(item);
+                            *sequence = Some (rest);
+                            ControlAction::Continue
+                        }
+                        _ => ControlAction::Error ("Sequence did not yield an item".to_string()),
+                    },
+                    Some (Value::Boolean (false)) => {
+                        data_stack.push(Value::List (mem::take(items)));
+                        ControlAction::Pop
+                    }
+                    _ => ControlAction::Error ("Sequence did not yield an item".to_string()),
+                },
+            },
+            Self::Map { function, items, mapped, pending } => {
+                if *pending {
+                    let Some (result) = data_stack.pop() else {
+                        return ControlAction::Error ("Function of `map` must leave a result on \
+                        the stack".to_string())
+                    };
+                    mapped.push(result);
+                }
+                match items.next() {
+                    Some (item) => {
+                        data_stack.push(item);
+                        *pending = true;
+                        ControlAction::Push (function.clone())
+                    }
+                    None => {
+                        data_stack.push(Value::List (mem::take(mapped)));
+                        ControlAction::Pop
+                    }
+                }
+            },
+            Self::Next { sequence, state } => sequence.pull(state, data_stack, environment),
+            Self::Spread { applications } => match applications.next() {
+                Some ((value, function)) => {
+                    data_stack.push(value);
+                    ControlAction::Push (function.get(environment))
+                }
+                None => ControlAction::Pop,
+            },
+            Self::SortBy { function, keyed, items, pending } => {
+                if let Some (item) = pending.take() {
+                    let Some (key) = data_stack.pop() else {
+                        return ControlAction::Error ("Key function of `sort-by` must leave a key \
+                        on the stack".to_string())
+                    };
+                    keyed.push((key, item));
+                }
+                match items.next() {
+                    Some (item) => {
+                        data_stack.push(item.clone());
+                        *pending = Some (item);
+                        ControlAction::Push (function.clone())
+                    }
+                    None => {
+                        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+                        data_stack.push(Value::List (mem::take(keyed).into_iter()
+                            .map(|(_, item)| item)
+                            .collect()
+                        ));
+                        ControlAction::Pop
+                    }
+                }
+            },
+            Self::Times { function, remaining } => if *remaining == 0 {
+                ControlAction::Pop
+            } else {
+                *remaining -= 1;
+                ControlAction::Push (function.clone())
+            },
+            Self::Reset { body, .. } => match body.take() {
+                Some (body) => ControlAction::Push (body),
+                None => ControlAction::Pop,
+            },
+            // the stack no longer needs to be restored once the body has been applied
+            Self::Try { body, .. } => match body.take() {
+                Some (body) => ControlAction::Push (body),
+                None => {
+                    data_stack.remove_guard();
+                    ControlAction::Pop
+                }
+            },
+            Self::While { body, condition, expected, testing } => if !*testing {
+                *testing = true;
+                ControlAction::Push (condition.clone())
+            } else {
+                *testing = false;
+                match data_stack.pop() {
+                    Some (Value::Boolean (boolean)) if boolean == *expected =>
+                        ControlAction::Push (body.clone()),
+                    Some (Value::Boolean (_)) => ControlAction::Pop,
+                    _ => ControlAction::Error ("Loop condition must evaluate to a boolean"
+                        .to_string()),
+                }
+            },
+        }
+    }
+
+}
+
+
+
+/// Evaluates a `Term` other than `Term::Recursion`, which must be evaluated by the `ControlFrame`
+/// that is evaluating it
+pub fn apply_term<'a>(
+    term: &Term,
+    locals: &mut Vec<Value>,
+    data_stack: &mut DataStack,
+    environment: &'a Environment,
+) -> ControlAction<'a> {
+    match term {
+        Term::Application (reference) => {
+            let function: Function = reference.get(environment);
+            ControlAction::Push (function)
+        },
+        Term::Bind (slot, names) => bind_locals(*slot, names.len(), locals, data_stack),
+        Term::Closure (reference, slots) => {
+            let captured: Vec<Value> = slots.iter()
+                .map(|slot| locals[*slot].clone())
+                .collect();
+            data_stack.push(Value::Lambda (LambdaReference::from_closure(
+                environment,
+                *reference,
+                captured
+            )));
+            ControlAction::Continue
+        },
+        Term::Combinator (combinator) => combinator.evaluate(data_stack, environment),
+        Term::Data (data) => {
+            data_stack.push(data.clone());
+            ControlAction::Continue
+        },
+        Term::Local (slot) => {
+            data_stack.push(locals[*slot].clone());
+            ControlAction::Continue
+        },
+        Term::Record (operation) => operation.evaluate(data_stack),
+        Term::Recursion => unreachable!("Recursion is evaluated by the frame that contains it"),
+        Term::Unbind (slot) => {
+            locals.truncate(*slot);
+            ControlAction::Continue
+        },
     }
+}
 
+/// Pops a number of values off the stack and binds them to the local slots of a frame, starting at
+/// a slot index
+pub fn bind_locals<'a>(
+    slot: usize,
+    count: usize,
+    locals: &mut Vec<Value>,
+    data_stack: &mut DataStack,
+) -> ControlAction<'a> {
+    match data_stack.pop_slice(count) {
+        Some (values) => {
+            locals.truncate(slot);
+            locals.extend(values);
+            ControlAction::Continue
+        }
+        None => ControlAction::Error ("Not enough items in the stack to bind local names"
+            .to_string()),
+    }
+}
+
+/// Evaluates the next term of a `Function` in a `ControlFrame`
+fn evaluate_term<'a>(
+    function: &Function<'a>,
+    index: &mut usize,
+    locals: &mut Vec<Value>,
+    data_stack: &mut DataStack,
+    environment: &'a Environment,
+) -> ControlAction<'a> {
+    let Some (term) = function.term(*index) else { return ControlAction::Pop };
+    let action: ControlAction = match term {
+        Term::Recursion => ControlAction::Push (function.clone()),
+        term => apply_term(term, locals, data_stack, environment),
+    };
+    *index += 1;
+    action
 }
 
 
@@ -90,6 +608,76 @@ impl<'a> ControlStack<'a> {
     /// Removes the `ControlFrame` from the top of this `ControlStack`
     pub fn pop_frame(&mut self) -> Option<ControlFrame<'a>> { self.0.pop() }
 
+    /// Captures the `ControlFrame`s above the nearest `reset` frame as a `Continuation`, removing
+    /// them and the data added to the stack since the `reset` was entered
+    pub fn capture(&mut self, data_stack: &mut DataStack) -> Option<Continuation> {
+        let reset: usize = self.0.iter()
+            .rposition(|frame| matches!(frame, ControlFrame::Reset { .. }))?;
+        let ControlFrame::Reset { depth, .. } = self.0[reset] else { unreachable!() };
+        // captured `try`s stop guarding the stack, and guard it again from the same relative
+        // depth when the continuation is resumed
+        for frame in self.0[reset + 1..].iter().rev() {
+            if matches!(frame, ControlFrame::Try { .. }) { data_stack.remove_guard(); }
+        }
+        let data: Vec<Value> = data_stack.pop_slice(data_stack.size().saturating_sub(depth))?;
+        let frames: Vec<ControlFrame<'static>> = self.0.drain(reset + 1..)
+            .map(|frame| match frame {
+                ControlFrame::Try { body, depth: try_depth, handler } => ControlFrame::Try {
+                    body,
+                    depth: try_depth.saturating_sub(depth),
+                    handler,
+                },
+                frame => frame,
+            })
+            .map(ControlFrame::into_owned)
+            .collect();
+        Some (Continuation (Arc::new((frames, data))))
+    }
+
+    /// Resumes a `Continuation` inside a new `reset` frame, restoring the data it captured to the
+    /// top of the stack
+    pub fn resume(&mut self, data_stack: &mut DataStack, continuation: &Continuation) {
+        let (frames, data) = &*continuation.0;
+        let depth: usize = data_stack.size();
+        self.push_frame(ControlFrame::Reset { body: None, depth });
+        for value in data {
+            data_stack.push(value.clone());
+        }
+        for frame in frames {
+            self.push_frame(match frame {
+                ControlFrame::Try { body, depth: try_depth, handler } => {
+                    data_stack.add_guard(depth + try_depth);
+                    ControlFrame::Try {
+                        body: body.clone(),
+                        depth: depth + try_depth,
+                        handler: handler.clone(),
+                    }
+                }
+                frame => frame.clone(),
+            });
+        }
+    }
+
+    /// Unwinds this `ControlStack` to the nearest `try` frame, restoring the data stack to its
+    /// state when the `try` was entered and applying the handler to the error, or returns the error
+    /// if there is no `try` frame
+    pub fn unwind(
+        &mut self,
+        data_stack: &mut DataStack,
+        environment: &Environment,
+        error: Value,
+    ) -> Result<(), Value> {
+        while let Some (frame) = self.pop_frame() {
+            if let ControlFrame::Try { handler, .. } = frame {
+                data_stack.restore_guard();
+                data_stack.push(error);
+                self.push_frame(ControlFrame::from_function(handler, environment));
+                return Ok (());
+            }
+        }
+        Err (error)
+    }
+
     /// Adds a new `ControlFrame` to this `ControlStack`
     pub fn push_frame(&mut self, frame: ControlFrame<'a>) { self.0.push(frame) }
 
@@ -1,23 +1,52 @@
 // Copyright Rob Gage 2025
 
+use super::{
+    Continuation,
+    Record,
+    Sequence,
+};
 use crate::{
+    Float,
     LambdaReference,
     Integer,
     Namespace,
+    Rational,
 };
 use smallvec::{
     SmallVec,
     smallvec,
 };
-use std::cell::UnsafeCell;
+use std::{
+    cell::UnsafeCell,
+    cmp::Ordering,
+    collections::BTreeMap,
+    hash::{
+        Hash,
+        Hasher,
+    },
+};
 
-/// Data that can be stored on the `Stack`
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Data that can be stored on the `Stack`, ordered first by kind and then by contents, where
+/// integers and rationals are the same kind and are ordered by their numeric values, and floats
+/// are a separate kind ordered after them
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Value {
 
     /// A true or false value
     Boolean (bool),
 
+    /// A string of bytes
+    Bytes (Vec<u8>),
+
+    /// A delimited continuation that can be resumed
+    Continuation (Continuation),
+
+    /// An error raised by a built-in operation, with a message describing it
+    Error (String),
+
+    /// An approximate floating-point number
+    Float (Float),
+
     /// An unbounded signed integer
     Integer (Integer),
 
@@ -27,6 +56,108 @@ pub enum Value {
     /// A list of `Data`
     List (Vec<Value>),
 
+    /// An ordered map from keys to values, which may both be any `Data`
+    Map (BTreeMap<Value, Value>),
+
+    /// An exact fraction that is not a whole number
+    Rational (Rational),
+
+    /// A value of a record type declared in source, with named fields
+    Record (Record),
+
+    /// A lazily evaluated sequence of `Data`
+    Sequence (Sequence),
+
+}
+
+impl Value {
+
+    /// Creates a `Value` from a `Rational`, which is an integer if the `Rational` is a whole number
+    pub fn from_rational(rational: Rational) -> Self {
+        match rational.to_integer() {
+            Some (integer) => Self::Integer (integer),
+            None => Self::Rational (rational),
+        }
+    }
+
+    /// Returns whether this `Value` is identical to another, which for lambdas means that one is a
+    /// copy of the other, and for every other kind of value means that they are equal
+    pub fn is_identical(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Lambda (a), Self::Lambda (b)) => a.is_identical(b),
+            _ => self == other,
+        }
+    }
+
+    /// Returns the position of the kind of this `Value` in the ordering of kinds
+    const fn kind_rank(&self) -> usize {
+        match self {
+            Self::Boolean (_) => 0,
+            Self::Bytes (_) => 1,
+            Self::Continuation (_) => 2,
+            Self::Error (_) => 3,
+            Self::Integer (_) | Self::Rational (_) => 4,
+            Self::Float (_) => 5,
+            Self::Lambda (_) => 6,
+            Self::List (_) => 7,
+            Self::Map (_) => 8,
+            Self::Record (_) => 9,
+            Self::Sequence (_) => 10,
+        }
+    }
+
+}
+
+impl Hash for Value {
+
+    /// Hashes the kind and contents of this `Value`, except that only the kind of a continuation,
+    /// lambda or sequence is hashed, so that equal values have equal hashes
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind_rank().hash(state);
+        match self {
+            Self::Boolean (boolean) => boolean.hash(state),
+            Self::Bytes (bytes) => bytes.hash(state),
+            Self::Error (message) => message.hash(state),
+            Self::Float (float) => float.hash(state),
+            Self::Integer (integer) => integer.hash(state),
+            Self::List (items) => items.hash(state),
+            Self::Map (map) => map.hash(state),
+            Self::Rational (rational) => rational.hash(state),
+            Self::Record (record) => record.hash(state),
+            Self::Continuation (_) | Self::Lambda (_) | Self::Sequence (_) => {}
+        }
+    }
+
+}
+
+impl Ord for Value {
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Boolean (a), Self::Boolean (b)) => a.cmp(b),
+            (Self::Bytes (a), Self::Bytes (b)) => a.cmp(b),
+            (Self::Continuation (a), Self::Continuation (b)) => a.cmp(b),
+            (Self::Error (a), Self::Error (b)) => a.cmp(b),
+            (Self::Float (a), Self::Float (b)) => a.cmp(b),
+            (Self::Integer (a), Self::Integer (b)) => a.cmp(b),
+            (Self::Integer (a), Self::Rational (b)) => Rational::from_integer(a.clone()).cmp(b),
+            (Self::Lambda (a), Self::Lambda (b)) => a.cmp(b),
+            (Self::List (a), Self::List (b)) => a.cmp(b),
+            (Self::Map (a), Self::Map (b)) => a.cmp(b),
+            (Self::Rational (a), Self::Integer (b)) => a.cmp(&Rational::from_integer(b.clone())),
+            (Self::Rational (a), Self::Rational (b)) => a.cmp(b),
+            (Self::Record (a), Self::Record (b)) => a.cmp(b),
+            (Self::Sequence (a), Self::Sequence (b)) => a.cmp(b),
+            _ => self.kind_rank().cmp(&other.kind_rank()),
+        }
+    }
+
+}
+
+impl PartialOrd for Value {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+
 }
 
 
@@ -38,12 +169,47 @@ const STACK_STACK_SIZE: usize = 1024;
 pub struct DataStack {
     /// The buffer containing the data on the stack
     buffer: UnsafeCell<SmallVec<[Value; STACK_STACK_SIZE]>>,
+    /// The `Guard`s of the `try`s being evaluated, innermost last
+    guards: UnsafeCell<Vec<Guard>>,
     /// The size of the stack
     top: usize
 }
 
 impl DataStack {
 
+    /// Starts recording the values that are removed or changed below a depth of the stack, so
+    /// that `restore_guard` can restore the stack to its values below that depth and discard the
+    /// values above it
+    pub fn add_guard(&mut self, depth: usize) {
+        let depth: usize = depth.min(self.top);
+        // only the innermost guard records values, so the values it does not record must already
+        // be recorded by the guard around it
+        self.preserve(depth);
+        self.guards.get_mut().push(Guard { depth, floor: depth, saved: Vec::new() });
+    }
+
+    /// Stops recording values for the innermost `Guard`, passing the values it recorded to the
+    /// `Guard` around it, which still needs them to restore the stack
+    pub fn remove_guard(&mut self) {
+        let guards: &mut Vec<Guard> = self.guards.get_mut();
+        let Some (mut inner) = guards.pop() else { return };
+        if let Some (outer) = guards.last_mut() {
+            if inner.floor < outer.floor {
+                outer.saved.extend(inner.saved.drain(inner.depth - outer.floor..));
+                outer.floor = inner.floor;
+            }
+        }
+    }
+
+    /// Restores the stack to its state when the innermost `Guard` was added, and removes it
+    pub fn restore_guard(&mut self) {
+        let Some (guard) = self.guards.get_mut().pop() else { return };
+        let buffer: &mut SmallVec<[Value; STACK_STACK_SIZE]> = self.buffer.get_mut();
+        buffer.truncate(guard.floor);
+        buffer.extend(guard.saved.into_iter().rev());
+        self.top = guard.depth;
+    }
+
     /// Returns the items in this stack as a `&[Data]`
     pub fn items(&self) -> SmallVec<[Value; STACK_STACK_SIZE]> {
         unsafe { (*self.buffer.get()).clone() }
@@ -53,6 +219,7 @@ impl DataStack {
     pub fn new() -> Self {
         Self {
             buffer: UnsafeCell::new(smallvec![]),
+            guards: UnsafeCell::new(Vec::new()),
             top: 0,
         }
     }
@@ -71,6 +238,7 @@ impl DataStack {
     /// (`0` is the index for the top)
     pub fn get_mutable_from_top(&self, index: usize) -> Option<&mut Value> {
         let stack_index: usize = self.top.checked_sub(1 + index)?;
+        self.preserve(stack_index);
         unsafe {
             let buffer: &mut SmallVec<[Value; STACK_STACK_SIZE]> = &mut *self.buffer.get();
             buffer.get_mut(stack_index)
@@ -81,6 +249,7 @@ impl DataStack {
     pub fn pop(&mut self) -> Option<Value> {
         if self.top == 0 { return None; }
         self.top -= 1;
+        self.preserve(self.top);
         unsafe {
             Some((*self.buffer.get()).pop().unwrap())
         }
@@ -91,13 +260,26 @@ impl DataStack {
     pub fn pop_slice(&mut self, index: usize) -> Option<Vec<Value>> {
         if index > self.top { return None };
         let slice_start: usize = self.top - index;
+        self.preserve(slice_start);
         self.top = slice_start;
         unsafe {
             let buffer = &mut *self.buffer.get();
             Some(buffer.drain(slice_start..).collect())
         }
     }
-    
+
+    /// Records the values from an index of the stack (`0` is the index for the bottom) up to the
+    /// floor of the innermost `Guard` before they are removed or changed
+    fn preserve(&self, stack_index: usize) {
+        unsafe {
+            let Some (guard) = (*self.guards.get()).last_mut() else { return };
+            if stack_index >= guard.floor { return; }
+            let buffer: &SmallVec<[Value; STACK_STACK_SIZE]> = &*self.buffer.get();
+            guard.saved.extend(buffer[stack_index..guard.floor].iter().rev().cloned());
+            guard.floor = stack_index;
+        }
+    }
+
     /// Pushes a `Value` onto the top of the stack
     pub fn push(&mut self, data: Value) {
         unsafe {
@@ -118,8 +300,23 @@ impl Clone for DataStack {
         let cloned: SmallVec<[Value; STACK_STACK_SIZE]> = unsafe { (&*self.buffer.get()).clone() };
         Self {
             buffer: UnsafeCell::new(cloned),
+            guards: UnsafeCell::new(unsafe { (*self.guards.get()).clone() }),
             top: self.top,
         }
     }
 
-}
\ No newline at end of file
+}
+
+
+
+/// Records the values that a `try` needs to restore the stack if its body fails, which are the
+/// values that were removed or changed below the size of the stack when the `try` was entered
+#[derive(Clone)]
+struct Guard {
+    /// The size of the stack when the `Guard` was added
+    depth: usize,
+    /// The lowest index of the stack whose value has been recorded, or the depth if none have
+    floor: usize,
+    /// The original values from the index one below the depth down to the floor
+    saved: Vec<Value>,
+}
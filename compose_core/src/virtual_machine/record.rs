@@ -0,0 +1,194 @@
+// Copyright Rob Gage 2025
+
+use crate::Value;
+use std::sync::Arc;
+use super::{
+    ControlAction,
+    DataStack,
+};
+
+/// The type of a record, made of its name and the names of its fields in order, and the union it
+/// is a variant of if it was declared by a union declaration
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RecordType {
+    /// The name of the type
+    name: String,
+    /// The names of the fields of the type, in order
+    fields: Vec<String>,
+    /// The union the type is a variant of, with the index of the type among its variants
+    union: Option<(Arc<UnionType>, usize)>,
+}
+
+impl RecordType {
+
+    /// Creates a new `RecordType` from its name and the names of its fields
+    pub fn new(name: String, fields: Vec<String>) -> Self { Self { name, fields, union: None } }
+
+    /// Creates a new `RecordType` that is the variant of a union at an index
+    pub fn variant(
+        name: String,
+        fields: Vec<String>,
+        union: Arc<UnionType>,
+        index: usize,
+    ) -> Self {
+        Self { name, fields, union: Some ((union, index)) }
+    }
+
+    /// Returns the names of the fields of this `RecordType`
+    pub fn fields(&self) -> &[String] { &self.fields }
+
+    /// Returns the name of this `RecordType`
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the union this `RecordType` is a variant of with its index among the union's
+    /// variants, if it is a variant of one
+    pub fn union(&self) -> Option<(&UnionType, usize)> {
+        self.union.as_ref().map(|(union, index)| (&**union, *index))
+    }
+
+}
+
+
+
+/// The type of a tagged union, made of its name and the names of its variants in order, where each
+/// variant is a `RecordType`
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UnionType {
+    /// The name of the union
+    name: String,
+    /// The names of the variants of the union, in order
+    variants: Vec<String>,
+}
+
+impl UnionType {
+
+    /// Creates a new `UnionType` from its name and the names of its variants
+    pub fn new(name: String, variants: Vec<String>) -> Self { Self { name, variants } }
+
+    /// Returns the name of this `UnionType`
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the names of the variants of this `UnionType`
+    pub fn variants(&self) -> &[String] { &self.variants }
+
+}
+
+
+
+/// A value with a `RecordType`, holding a value for each of its fields, where records are equal if
+/// their types and values are equal
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Record {
+    /// The type of the record
+    kind: Arc<RecordType>,
+    /// The values of the fields of the record, in the order of its type's fields
+    values: Vec<Value>,
+}
+
+impl Record {
+
+    /// Returns the names of the fields of this `Record` with their values
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.kind.fields().iter().map(String::as_str).zip(self.values.iter())
+    }
+
+    /// Returns the values of the fields of this `Record`, in order
+    pub fn into_values(self) -> Vec<Value> { self.values }
+
+    /// Returns the `RecordType` of this `Record`
+    pub fn kind(&self) -> &RecordType { &self.kind }
+
+}
+
+
+
+/// An operation on the records of one `RecordType`, generated as a function by a record
+/// declaration
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RecordOperation {
+
+    /// Creates a record from a value for each of its fields, with the last field on top of the
+    /// stack
+    Construct (Arc<RecordType>),
+
+    /// Turns a record into the value of the field at an index
+    Get (Arc<RecordType>, usize),
+
+    /// Turns a value into whether it is a record of the type
+    Test (Arc<RecordType>),
+
+    /// Replaces the value of the field at an index of a record (second from top of the stack)
+    /// with the value on top of the stack
+    Update (Arc<RecordType>, usize),
+
+}
+
+impl RecordOperation {
+
+    /// Returns the operations generated by the declaration of a `RecordType`, which are a
+    /// constructor, an accessor and an updater for each field, and a predicate
+    pub fn declared(kind: RecordType) -> Vec<Self> {
+        let kind: Arc<RecordType> = Arc::new(kind);
+        let mut operations: Vec<Self> = vec![Self::Construct (kind.clone())];
+        for index in 0..kind.fields().len() {
+            operations.push(Self::Get (kind.clone(), index));
+            operations.push(Self::Update (kind.clone(), index));
+        }
+        operations.push(Self::Test (kind));
+        operations
+    }
+
+    /// Evaluates this `RecordOperation` on a `DataStack`
+    pub fn evaluate<'a>(&self, data_stack: &mut DataStack) -> ControlAction<'a> {
+        match self {
+            Self::Construct (kind) => match data_stack.pop_slice(kind.fields().len()) {
+                Some (values) => {
+                    data_stack.push(Value::Record (Record { kind: kind.clone(), values }));
+                    ControlAction::Continue
+                }
+                None => ControlAction::Error (format!("Cannot perform `{}` unless there are {} \
+                values on the stack", self.name(), kind.fields().len())),
+            },
+            Self::Get (kind, index) => match data_stack.pop() {
+                Some (Value::Record (mut record)) if record.kind == *kind => {
+                    data_stack.push(record.values.swap_remove(*index));
+                    ControlAction::Continue
+                }
+                _ => ControlAction::Error (format!("Cannot perform `{}` unless there is a `{}` \
+                record on top of the stack", self.name(), kind.name())),
+            },
+            Self::Test (kind) => match data_stack.pop() {
+                Some (value) => {
+                    let matches: bool
+                        = matches!(value, Value::Record (record) if record.kind == *kind);
+                    data_stack.push(Value::Boolean (matches));
+                    ControlAction::Continue
+                }
+                None => ControlAction::Error (format!("Cannot perform `{}` on an empty stack",
+                    self.name())),
+            },
+            Self::Update (kind, index) => match (data_stack.pop(), data_stack.pop()) {
+                (Some (value), Some (Value::Record (mut record))) if record.kind == *kind => {
+                    record.values[*index] = value;
+                    data_stack.push(Value::Record (record));
+                    ControlAction::Continue
+                }
+                _ => ControlAction::Error (format!("Cannot perform `{}` unless there is a value \
+                above a `{}` record on top of the stack", self.name(), kind.name())),
+            },
+        }
+    }
+
+    /// Returns the name of the function that performs this `RecordOperation`, such as `point` for
+    /// the constructor of a `point` record, `point_x` and `set_point_x` for the accessor and
+    /// updater of its `x` field, and `is_point` for its predicate
+    pub fn name(&self) -> String {
+        match self {
+            Self::Construct (kind) => kind.name().to_string(),
+            Self::Get (kind, index) => format!("{}_{}", kind.name(), kind.fields()[*index]),
+            Self::Test (kind) => format!("is_{}", kind.name()),
+            Self::Update (kind, index) => format!("set_{}_{}", kind.name(), kind.fields()[*index]),
+        }
+    }
+
+}
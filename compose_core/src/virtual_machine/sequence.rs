@@ -0,0 +1,277 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Environment,
+    Integer,
+    LambdaReference,
+    Value,
+};
+use std::sync::Arc;
+use super::{
+    ControlAction,
+    ControlFrame,
+    DataStack,
+};
+
+/// A lazily evaluated sequence of values, whose items are computed as they are pulled
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Sequence {
+
+    /// Skips the items of a `Sequence` while a predicate lambda holds for them
+    DropWhile (Box<Sequence>, LambdaReference),
+
+    /// Keeps only the items of a `Sequence` that a predicate lambda holds for
+    Filter (Box<Sequence>, LambdaReference),
+
+    /// An infinite `Sequence` that starts with a seed value, where each following item is made by
+    /// applying a step lambda to the previous one
+    Iterate (Box<Value>, LambdaReference),
+
+    /// The items of a list, starting at an index
+    List (Arc<[Value]>, usize),
+
+    /// Applies a lambda to each item of a `Sequence`
+    Map (Box<Sequence>, LambdaReference),
+
+    /// An infinite `Sequence` of integers that counts from a start (first field) by a step (second
+    /// field)
+    Range (Integer, Integer),
+
+    /// Keeps only a number of items from the start of a `Sequence`
+    Take (Box<Sequence>, usize),
+
+    /// Keeps the items of a `Sequence` until a predicate lambda does not hold for one of them
+    TakeWhile (Box<Sequence>, LambdaReference),
+
+}
+
+impl Sequence {
+
+    /// Creates a `Sequence` from a sequence or a list `Value`
+    pub fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::List (items) => Some (Self::List (items.into(), 0)),
+            Value::Sequence (sequence) => Some (sequence),
+            _ => None
+        }
+    }
+
+    /// Runs one step of pulling the next item from this `Sequence`, which eventually leaves the
+    /// item, the rest of the `Sequence` and `true` on the stack, or `false` if it is exhausted
+    pub fn pull<'a>(
+        &mut self,
+        state: &mut Pull,
+        data_stack: &mut DataStack,
+        environment: &'a Environment,
+    ) -> ControlAction<'a> {
+        use Sequence::*;
+        match (self, &*state) {
+            // lists and the start of iteration need no sources to be pulled
+            (List (items, index), _) => {
+                if let Some (item) = items.get(*index) {
+                    data_stack.push(item.clone());
+                    yielded(data_stack, List (items.clone(), *index + 1))
+                } else { exhausted(data_stack) }
+            }
+            (Range (start, step), _) => {
+                let next: Integer = start.clone() + step.clone();
+                data_stack.push(Value::Integer (start.clone()));
+                yielded(data_stack, Range (next, step.clone()))
+            }
+            (Iterate (seed, step), Pull::Start) => {
+                data_stack.push((**seed).clone());
+                data_stack.push((**seed).clone());
+                *state = Pull::Pulled;
+                ControlAction::Push (step.get(environment))
+            }
+            (Iterate (_, step), _) => match data_stack.pop() {
+                Some (seed) => yielded(data_stack, Iterate (Box::new(seed), step.clone())),
+                None => ControlAction::Error ("Step function of an `iterate` sequence must leave \
+                a value on the stack".to_string()),
+            }
+            (Take (_, 0), Pull::Start) => exhausted(data_stack),
+            // pull the next item from the source of a sequence
+            (DropWhile (source, _) | Filter (source, _) | Map (source, _) | Take (source, _)
+            | TakeWhile (source, _), Pull::Start) => {
+                *state = Pull::Pulled;
+                ControlAction::PushFrame (ControlFrame::Next {
+                    sequence: (**source).clone(),
+                    state: Pull::Start,
+                })
+            }
+            // handle the item pulled from the source of a sequence
+            (sequence, Pull::Pulled) => {
+                let rest: Sequence = match data_stack.pop() {
+                    Some (Value::Boolean (false)) => return exhausted(data_stack),
+                    Some (Value::Boolean (true)) => match data_stack.pop() {
+                        Some (Value::Sequence (rest)) => rest,
+                        _ => return ControlAction::Error ("Sequence source did not yield an item"
+                            .to_string()),
+                    }
+                    _ => return ControlAction::Error ("Sequence source did not yield an item"
+                        .to_string()),
+                };
+                match sequence {
+                    Take (_, count) => yielded(data_stack, Take (Box::new(rest), *count - 1)),
+                    Map (_, function) => {
+                        *state = Pull::Applied (rest);
+                        ControlAction::Push (function.get(environment))
+                    }
+                    DropWhile (_, predicate) | Filter (_, predicate) | TakeWhile (_, predicate) => {
+                        let Some (item) = data_stack.get_from_top(0) else {
+                            return ControlAction::Error ("Sequence source did not yield an item"
+                                .to_string())
+                        };
+                        data_stack.push(item.clone());
+                        *state = Pull::Applied (rest);
+                        ControlAction::Push (predicate.get(environment))
+                    }
+                    _ => unreachable!("Only sequences with sources pull items from them"),
+                }
+            }
+            // handle the result of applying a function to the pulled item
+            (sequence, Pull::Applied (rest)) => {
+                let rest: Sequence = rest.clone();
+                if let Map (_, function) = sequence {
+                    return yielded(data_stack, Map (Box::new(rest), function.clone()));
+                }
+                let Some (Value::Boolean (holds)) = data_stack.pop() else {
+                    return ControlAction::Error ("Sequence predicate must evaluate to a boolean"
+                        .to_string())
+                };
+                match (sequence, holds) {
+                    (Filter (_, predicate), true) =>
+                        yielded(data_stack, Filter (Box::new(rest), predicate.clone())),
+                    (TakeWhile (_, predicate), true) =>
+                        yielded(data_stack, TakeWhile (Box::new(rest), predicate.clone())),
+                    (DropWhile (_, _), false) => yielded(data_stack, rest),
+                    (TakeWhile (_, _), false) => {
+                        data_stack.pop();
+                        exhausted(data_stack)
+                    }
+                    // skip the item, and pull the next item from the rest of the source
+                    (DropWhile (source, _) | Filter (source, _), _) => {
+                        data_stack.pop();
+                        **source = rest;
+                        *state = Pull::Start;
+                        ControlAction::Continue
+                    }
+                    _ => unreachable!("Only sequences with predicates test pulled items"),
+                }
+            }
+        }
+    }
+
+}
+
+
+
+/// The state of a `ControlFrame` pulling the next item from a `Sequence`
+#[derive(Clone)]
+pub enum Pull {
+    /// No item has been pulled yet
+    Start,
+    /// An item has been pulled from the source of the `Sequence`
+    Pulled,
+    /// A function has been applied to the pulled item, with the rest of the source remaining
+    Applied (Sequence),
+}
+
+
+
+/// Finishes pulling from a `Sequence` that has no more items
+fn exhausted<'a>(data_stack: &mut DataStack) -> ControlAction<'a> {
+    data_stack.push(Value::Boolean (false));
+    ControlAction::Pop
+}
+
+
+/// Finishes pulling an item from a `Sequence`, where the item is already on top of the stack
+fn yielded<'a>(data_stack: &mut DataStack, rest: Sequence) -> ControlAction<'a> {
+    data_stack.push(Value::Sequence (rest));
+    data_stack.push(Value::Boolean (true));
+    ControlAction::Pop
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::testing::evaluate_bare;
+
+    #[test]
+    fn ranges_to_infinity_count_without_end() {
+        assert_eq!(evaluate_bare("0 inf range 5 take force"), Ok ("[ 0 1 2 3 4 ]".to_string()));
+        assert_eq!(evaluate_bare("0 inf range next"), Ok ("0 sequence true".to_string()));
+        assert_eq!(
+            evaluate_bare("10 inf -1e0 * -3 range-step 4 take force"),
+            Ok ("[ 10 7 4 1 ]".to_string())
+        );
+    }
+
+    #[test]
+    fn ranges_to_infinity_against_their_step_are_empty() {
+        assert_eq!(evaluate_bare("10 inf -3 range-step"), Ok ("[ ]".to_string()));
+    }
+
+    #[test]
+    fn ranges_to_floats_need_an_infinite_end_and_a_nonzero_step() {
+        assert_eq!(
+            evaluate_bare("0 1.5e0 range"),
+            Err ("Cannot perform `range` with a finite float as its end".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("0 inf 0 range-step"),
+            Err ("Cannot perform `range-step` with a step of zero".to_string())
+        );
+    }
+
+    #[test]
+    fn iterate_applies_its_step_to_each_item() {
+        assert_eq!(
+            evaluate_bare("1 ( 2 * ) iterate 5 take force"),
+            Ok ("[ 1 2 4 8 16 ]".to_string())
+        );
+        assert_eq!(evaluate_bare("1 ( 2 * ) iterate 0 take force"), Ok ("[ ]".to_string()));
+    }
+
+    #[test]
+    fn lazy_map_and_lazy_filter_transform_items_as_they_are_pulled() {
+        assert_eq!(
+            evaluate_bare("0 inf range ( copy * ) lazy-map 4 take force"),
+            Ok ("[ 0 1 4 9 ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("0 inf range ( 2 % 0 = ) lazy-filter 3 take force"),
+            Ok ("[ 0 2 4 ]".to_string())
+        );
+    }
+
+    #[test]
+    fn take_while_and_drop_while_test_items_with_a_predicate() {
+        assert_eq!(
+            evaluate_bare("0 inf range ( 4 < ) take-while force"),
+            Ok ("[ 0 1 2 3 ]".to_string())
+        );
+        assert_eq!(
+            evaluate_bare("0 inf range ( 3 < ) drop-while 2 take force"),
+            Ok ("[ 3 4 ]".to_string())
+        );
+    }
+
+    #[test]
+    fn lists_can_be_used_as_sequences() {
+        assert_eq!(evaluate_bare("[ 1 2 3 ] 2 take force"), Ok ("[ 1 2 ]".to_string()));
+        assert_eq!(evaluate_bare("[ ] next"), Ok ("false".to_string()));
+    }
+
+    #[test]
+    fn sequence_predicates_must_be_booleans() {
+        assert_eq!(
+            evaluate_bare("0 inf range ( ) lazy-filter next"),
+            Err ("Sequence predicate must evaluate to a boolean".to_string())
+        );
+    }
+
+}
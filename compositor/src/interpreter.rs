@@ -2,10 +2,15 @@
 
 use colored::Colorize;
 use compose_core::{
+    literal_errors,
     Value,
     FunctionReference,
+    LoadError,
     Namespace,
     UnresolvedFunction,
+    UnresolvedImport,
+    UnresolvedRecord,
+    UnresolvedUnion,
     VirtualMachine,
 };
 use pups::*;
@@ -15,24 +20,44 @@ use rustyline::{
     history::DefaultHistory,
     Config
 };
-use std::process::exit;
+use std::{
+    env::{
+        current_dir,
+        split_paths,
+        var_os,
+    },
+    path::Path,
+    process::exit,
+};
 
 /// An interpreter for the `Compose` language
 pub struct Interpreter {
     /// The `Namespace` used by this `Interpreter`
     namespace: Namespace,
+    /// Whether the standard prelude is loaded into the `Namespace`
+    prelude: bool,
     /// The `VirtualMachine` used by this `Interpreter`
     virtual_machine: VirtualMachine
 }
 
 impl Interpreter {
 
-    /// Creates a new `Interpreter`
-    pub fn new() -> Self {
-        let namespace = Namespace::new();
+    /// Creates a new `Interpreter`, optionally loading the standard prelude
+    pub fn new(prelude: bool) -> Self {
+        let mut namespace = if prelude { Namespace::with_prelude() } else { Namespace::new() };
+        // search for modules in the working directory, then in the directories in `COMPOSE_PATH`
+        if let Ok (directory) = current_dir() {
+            namespace.add_search_path(directory);
+        }
+        if let Some (paths) = var_os("COMPOSE_PATH") {
+            for path in split_paths(&paths) {
+                namespace.add_search_path(path);
+            }
+        }
         let virtual_machine = namespace.create_virtual_machine();
         Self {
             namespace,
+            prelude,
             virtual_machine,
         }
     }
@@ -51,12 +76,89 @@ impl Interpreter {
                     }
                     // resets the `Interpreter`
                     "!reset" => {
-                        *self = Self::new();
+                        *self = Self::new(self.prelude);
                         return;
                     },
                     _ => {}
                 };
+                // loads a source file
+                if let Some (path) = input.strip_prefix("!load ") {
+                    let path: &str = path.trim();
+                    match self.namespace.load(Path::new(path)) {
+                        Ok (_) => println!("  {} {}", "Loaded file:".purple(), path.bright_purple()),
+                        Err (error) => eprintln!("  {} {}", "Error:".red(),
+                            error.to_string().red().dimmed()),
+                    }
+                    return;
+                }
+                // sets the radix that integers are printed in
+                if let Some (radix) = input.strip_prefix("!radix ") {
+                    match radix.trim().parse::<u32>() {
+                        Ok (radix @ (2 | 8 | 10 | 16)) => {
+                            self.namespace.set_integer_radix(radix);
+                            println!("  {} {}", "Printing integers in radix:".purple(),
+                                radix.to_string().bright_purple());
+                        }
+                        _ => eprintln!("  {} {}", "Error:".red(),
+                            "Radix must be 2, 8, 10 or 16".red().dimmed()),
+                    }
+                    return;
+                }
+                let source: &str = input;
                 let input: Text = Text::from_string(input);
+                // import modules
+                let result: ParseResult<_, _, _> =
+                    UnresolvedImport::parse.then_ignore(end()).parse(&input);
+                if let ParseResult::Success (import, _) = result {
+                    let module_name: &str = import.module_name();
+                    let imported: Result<(), LoadError> = if import.is_qualified() {
+                        self.namespace.import_qualified(module_name)
+                    } else { self.namespace.import(module_name) };
+                    match imported {
+                        Ok (_) => println!(
+                            "  {} {}",
+                            "Imported module:".purple(),
+                            module_name.bright_purple()
+                        ),
+                        Err (error) => eprintln!("  {} {}", "Error:".red(),
+                            error.to_string().red().dimmed()),
+                    }
+                    return;
+                }
+                // declare records
+                let result: ParseResult<_, _, _> =
+                    UnresolvedRecord::parse.then_ignore(end()).parse(&input);
+                if let ParseResult::Success (record, _) = result {
+                    if let Err (error) = record.validate() {
+                        eprintln!("  {} {}", "Error:".red(), error.to_string().red().dimmed());
+                        return;
+                    }
+                    for function in record.functions() {
+                        if let Err (error) = self.namespace.define(&function) {
+                            eprintln!("  {} {}", "Error:".red(), error.to_string().red().dimmed());
+                            return;
+                        }
+                    }
+                    println!("  {} {}", "Declared record:".purple(), record.name().bright_purple());
+                    return;
+                }
+                // declare unions
+                let result: ParseResult<_, _, _> =
+                    UnresolvedUnion::parse.then_ignore(end()).parse(&input);
+                if let ParseResult::Success (union, _) = result {
+                    if let Err (error) = union.validate() {
+                        eprintln!("  {} {}", "Error:".red(), error.to_string().red().dimmed());
+                        return;
+                    }
+                    for function in union.functions() {
+                        if let Err (error) = self.namespace.define(&function) {
+                            eprintln!("  {} {}", "Error:".red(), error.to_string().red().dimmed());
+                            return;
+                        }
+                    }
+                    println!("  {} {}", "Declared union:".purple(), union.name().bright_purple());
+                    return;
+                }
                 let result: ParseResult<_, _, _> =
                     UnresolvedFunction::parse.then_ignore(end()).parse(&input);
                 if let ParseResult::Success (unresolved_function, _) = result {
@@ -67,13 +169,18 @@ impl Interpreter {
                             "Defined function:".purple(),
                             unresolved_function.name().bright_purple()
                         ),
-                        Err (missing) => {
+                        Err (LoadError::Undefined (missing)) => {
                             println!("  {}", "Function not defined. Missing required functions:"
                                 .red().dimmed());
                             for name in missing {
                                 println!("    {}", name.red());
                             }
                         }
+                        Err (error) => eprintln!(
+                            "  {} {}",
+                            "Error:".red(),
+                            error.to_string().red().dimmed()
+                        ),
                     };
                     return;
                 } else if let ParseResult::Success (unresolved_function, _) =
@@ -84,7 +191,7 @@ impl Interpreter {
                     // define free terms as temporary function
                     let function: FunctionReference = match self.namespace.define(&unresolved_function) {
                         Ok (terms) => terms,
-                        Err (missing) => {
+                        Err (LoadError::Undefined (missing)) => {
                             println!("  {}", "Function not defined. Missing required functions:"
                                 .red().dimmed());
                             for name in missing {
@@ -92,6 +199,10 @@ impl Interpreter {
                             }
                             return;
                         }
+                        Err (error) => {
+                            eprintln!("  {} {}", "Error:".red(), error.to_string().red().dimmed());
+                            return;
+                        }
                     };
                     // evaluate free terms
                     match self.virtual_machine.evaluate(function) {
@@ -103,11 +214,23 @@ impl Interpreter {
                             }
                             println!("\n{}\n", printed_stack);
                         },
-                        Err (error) => eprintln!("  {} {}", "Error:".red(), error.red().dimmed()),
+                        Err (Value::Error (message)) =>
+                            eprintln!("  {} {}", "Error:".red(), message.red().dimmed()),
+                        Err (value) => {
+                            let mut printed_value: String = String::new();
+                            self.namespace.write_value(&mut printed_value, &value).unwrap();
+                            eprintln!("  {} {}", "Uncaught value:".red(), printed_value.red().dimmed())
+                        }
                     }
                 } else {
-                    println!("{}", "  Unrecognized input. This Compose interpreter only accepts
+                    let errors: Vec<String> = literal_errors(source);
+                    if errors.is_empty() {
+                        println!("{}", "  Unrecognized input. This Compose interpreter only accepts
                     functions to be defined, and free terms to be evaluated".red().dimmed());
+                    }
+                    for error in errors {
+                        eprintln!("  {} {}", "Error:".red(), error.red().dimmed());
+                    }
                 }
             }
             Err (ReadlineError::Interrupted) => {
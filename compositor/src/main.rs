@@ -5,9 +5,12 @@ mod interpreter;
 use interpreter::Interpreter;
 
 use colored::Colorize;
+use std::env::args;
 
 fn main() {
-    let mut interpreter: Interpreter = Interpreter::new();
+    // the standard prelude is loaded unless `--no-prelude` is passed
+    let prelude: bool = !args().skip(1).any(|argument| argument == "--no-prelude");
+    let mut interpreter: Interpreter = Interpreter::new(prelude);
     println!("\n    {}\n    Compose Interactive Environment\n\n", "Compositor".green().bold());
     interpreter.run();
 }
\ No newline at end of file